@@ -0,0 +1,100 @@
+//! Throughput of the rules engine: `cargo bench -p star_realms_engine`.
+//!
+//! Playouts are reported per second, which sizes the budgets of the search agents.
+use std::hint::black_box;
+use criterion::{criterion_group, criterion_main, BatchSize, Criterion, Throughput};
+use star_realms_engine::agents::{Agent, GreedyAgent, MonteCarloAgent, RandomAgent};
+use star_realms_engine::cards::Card;
+use star_realms_engine::gamelogic::{Action, GameLogic, Target};
+use star_realms_engine::star_realms::GameState;
+use star_realms_engine::tournament::play_game;
+
+const MAX_TURNS: i32 = 200;
+
+/// A state after a few random turns, with cards in every zone
+fn mid_game(seed: u64) -> GameState {
+    let mut gs = GameState::with_seed(seed);
+    let mut agent = RandomAgent::new(seed);
+    while gs.turn < 8 && !gs.is_over() {
+        gs = GameLogic::apply(&gs, &agent.act(&gs)).expect("Agents only choose legal actions");
+    }
+    gs
+}
+
+/// A fresh game whose first player holds `hand` and has `discard` in their discard pile
+fn with_cards(hand: &[Card], discard: &[Card]) -> GameState {
+    let mut gs = GameState::with_seed(1);
+    gs.players.0.hand.playable = hand.to_vec();
+    gs.players.0.discard = discard.to_vec();
+    gs.rehash();
+    gs
+}
+
+fn play_all(gs: &GameState, actions: &[Action]) -> GameState {
+    actions.iter().fold(gs.clone(), |gs, a| GameLogic::apply(&gs, a).expect("Benchmarked actions are legal"))
+}
+
+fn state(c: &mut Criterion) {
+    let mid = mid_game(3);
+    let action = GameLogic::legal_actions(&mid)[0];
+    let mut group = c.benchmark_group("state");
+    group.bench_function("new", |b| b.iter(|| GameState::with_seed(black_box(3))));
+    group.bench_function("clone", |b| b.iter(|| black_box(&mid).clone()));
+    group.bench_function("mutate_players", |b| b.iter(|| black_box(&mid).mutate_players(mid.get_current_player(), mid.turn % 2)));
+    group.bench_function("legal_actions", |b| b.iter(|| GameLogic::legal_actions(black_box(&mid))));
+    group.bench_function("apply", |b| b.iter(|| GameLogic::apply(black_box(&mid), &action)));
+    group.bench_function("zobrist", |b| b.iter(|| black_box(&mid).zobrist()));
+    group.finish();
+}
+
+fn abilities(c: &mut Criterion) {
+    let mut group = c.benchmark_group("abilities");
+    // Brain World: scrap a card from the hand and one from the discard pile, then draw two
+    let brain_world = with_cards(&[Card::BrainWorld, Card::Scout], &[Card::Viper]);
+    let resolve = [
+        Action::Activate(0),
+        Action::Select(Target::Playable(0)),
+        Action::Select(Target::Discarded(0)),
+        Action::Confirm,
+    ];
+    let played = play_all(&brain_world, &[Action::Play(0)]);
+    play_all(&played, &resolve);
+    group.bench_function("brain_world", |b| b.iter(|| play_all(black_box(&played), &resolve)));
+    // Stealth Needle: copy a Ram played before it
+    let needle = with_cards(&[Card::Ram, Card::StealthNeedle], &[]);
+    let copy = [Action::Play(0), Action::Select(Target::Played(0))];
+    let played = play_all(&needle, &[Action::Play(0)]);
+    play_all(&played, &copy);
+    group.bench_function("stealth_needle", |b| b.iter(|| play_all(black_box(&played), &copy)));
+    group.finish();
+}
+
+fn playouts(c: &mut Criterion) {
+    let mut group = c.benchmark_group("playouts");
+    group.sample_size(20);
+    group.throughput(Throughput::Elements(1));
+    let mut seed = 0;
+    group.bench_function("random_game", |b| b.iter_batched(
+        || { seed += 1; (RandomAgent::new(seed), RandomAgent::new(seed + 1), seed) },
+        |(mut first, mut second, seed)| play_game((&mut first, &mut second), seed, MAX_TURNS, |_, _| {}),
+        BatchSize::SmallInput,
+    ));
+    group.bench_function("greedy_game", |b| b.iter_batched(
+        || { seed += 1; seed },
+        |seed| play_game((&mut GreedyAgent, &mut GreedyAgent), seed, MAX_TURNS, |_, _| {}),
+        BatchSize::SmallInput,
+    ));
+    // one decision of the Monte Carlo agent, reported per playout
+    let mid = mid_game(5);
+    let budget = MonteCarloAgent::DEFAULT_PLAYOUTS;
+    group.throughput(Throughput::Elements(budget as u64));
+    group.bench_function("montecarlo_decision", |b| b.iter_batched(
+        || { seed += 1; MonteCarloAgent::new(seed, budget) },
+        |mut agent| agent.act(&mid),
+        BatchSize::SmallInput,
+    ));
+    group.finish();
+}
+
+criterion_group!(benches, state, abilities, playouts);
+criterion_main!(benches);
@@ -0,0 +1,658 @@
+use std::cmp::PartialEq;
+use std::fmt::{Debug, Display, Formatter};
+use std::ops::Deref;
+use std::sync::Arc;
+use crate::abilities::Ability::{Atomic, Delayed};
+use crate::abilities::AfterCapacity::{AllyToAll, NextShipOnTop, ScrapFromHand};
+use crate::abilities::ChoicesSources::{Discarded, EnemyBase, Playable, Played, TradeRow};
+use crate::cards::{Card, Faction};
+use crate::error::RuleError;
+use crate::events::Event;
+use crate::gamelogic::GameLogic;
+use crate::star_realms::{GameState, Player, Zone};
+
+#[derive(Clone)]
+pub struct Predicate {
+    description: String,
+    pred: fn(&GameState) -> bool,
+}
+
+impl Debug for Predicate {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Predicate")
+            .field("description", &self.description)
+            .field("pred", &"predicate <closure>")
+            .finish()
+    }
+}
+
+impl Predicate {
+    pub fn new(description: String, pred: fn(&GameState) -> bool) -> Predicate {
+        Predicate {
+            description,
+            pred
+        }
+    }
+
+    pub fn test(&self, gs: &GameState) -> bool {
+        (self.pred)(gs)
+    }
+
+    pub fn description(&self) -> &str {
+        &self.description
+    }
+}
+
+impl Display for Predicate {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.description)
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum ChoicesSources {
+    EnemyBase,
+    TradeRow,
+    Played,
+    Discarded,
+    Playable,
+    And(Box<ChoicesSources>, Box<ChoicesSources>)
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum AfterCapacity {
+    AllyToAll,
+    NextShipOnTop,
+    NextShipFree,
+    ScrapFromHand,
+    And(Box<AfterCapacity>, Box<AfterCapacity>)
+}
+
+/// What an atomic ability does, with its parameters
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub enum Effect {
+    Trade(i32),
+    Damage(i32),
+    Authority(i32),
+    Draw(i32),
+    /// Draw a card for each ship of the faction played this turn
+    DrawPerFaction(Faction),
+    NextShipOnTop,
+    FreeShipOnTop,
+    DestroyBase,
+    ScrapTradeRow,
+    /// The opponent discards a card at the start of their next turn
+    TargetDiscard,
+    /// Discard a card at the start of the turn, see `TargetDiscard`
+    Discard,
+    DiscardThenDraw { max: i32 },
+    /// Damages for each ship played this turn
+    ShipsGetDamage(i32),
+    ScrapHandOrDiscard { max: i32 },
+    ScrapThenDraw { max: i32 },
+    ScrapFromHand,
+    DrawThenScrap,
+    CopyShip,
+    AllyToAll,
+}
+
+impl Effect {
+    pub fn trade(&self) -> i32 {
+        match self {
+            Effect::Trade(n) => *n,
+            _ => 0,
+        }
+    }
+
+    pub fn damage(&self) -> i32 {
+        match self {
+            Effect::Damage(n) => *n,
+            _ => 0,
+        }
+    }
+
+    pub fn authority(&self) -> i32 {
+        match self {
+            Effect::Authority(n) => *n,
+            _ => 0,
+        }
+    }
+
+    /// Cards drawn, when it does not depend on the game
+    pub fn draws(&self) -> i32 {
+        match self {
+            Effect::Draw(n) => *n,
+            Effect::DrawThenScrap => 1,
+            _ => 0,
+        }
+    }
+}
+
+/// Rules text, as printed on the cards
+impl Display for Effect {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Effect::Trade(n) => write!(f, "Add {n} trade"),
+            Effect::Damage(n) => write!(f, "Add {n} combat"),
+            Effect::Authority(n) => write!(f, "Gain {n} authority"),
+            Effect::Draw(1) => write!(f, "Draw a card"),
+            Effect::Draw(n) => write!(f, "Draw {n} cards"),
+            Effect::DrawPerFaction(faction) => write!(f, "Draw a card for each {faction} card you've played this turn"),
+            Effect::NextShipOnTop => write!(f, "You may put the next ship you acquire this turn on top of your deck"),
+            Effect::FreeShipOnTop => write!(f, "Acquire any ship for free and put it on top of your deck"),
+            Effect::DestroyBase => write!(f, "You may destroy target base"),
+            Effect::ScrapTradeRow => write!(f, "You may scrap a card in the trade row"),
+            Effect::TargetDiscard => write!(f, "Target opponent discards a card"),
+            Effect::Discard => write!(f, "Discard a card"),
+            Effect::DiscardThenDraw { max } => write!(f, "Discard up to {max} cards, then draw that many cards"),
+            Effect::ShipsGetDamage(n) => write!(f, "All of your ships get {n} combat"),
+            Effect::ScrapHandOrDiscard { max: 1 } => write!(f, "You may scrap a card in your hand or discard pile"),
+            Effect::ScrapHandOrDiscard { max } => write!(f, "You may scrap up to {max} cards in your hand or discard pile"),
+            Effect::ScrapThenDraw { max } => write!(f, "You may scrap up to {max} cards in your hand or discard pile, then draw that many cards"),
+            Effect::ScrapFromHand => write!(f, "Scrap a card in your hand"),
+            Effect::DrawThenScrap => write!(f, "Draw a card, then scrap a card in your hand"),
+            Effect::CopyShip => write!(f, "Play this as a copy of another ship you've played this turn"),
+            Effect::AllyToAll => write!(f, "Counts as an ally for all factions"),
+        }
+    }
+}
+
+pub enum AtomicAbilityFn {
+    Default(Box<dyn Fn(&GameState) -> Result<GameState, RuleError> + Send + Sync>),
+    Card(Box<dyn Fn(&GameState, &Card) -> Result<GameState, RuleError> + Send + Sync>),
+    Cards(Box<dyn Fn(&GameState, &Vec<Card>) -> Result<GameState, RuleError> + Send + Sync>),
+    CardsFromHandOrDiscard(Box<dyn Fn(&GameState, &Vec<Card>, &Vec<Card>) -> Result<GameState, RuleError> + Send + Sync>),
+}
+#[derive(Clone)]
+pub struct AtomicAbility
+{
+    ability: Arc<AtomicAbilityFn>,
+    choices_sources: Option<ChoicesSources>,
+    after_capacity: Option<AfterCapacity>,
+    /// Cards to select before the ability can be confirmed, and that cannot be declined while
+    /// there is a card to select
+    min_choices: usize,
+    max_choices: usize,
+    effect: Effect,
+}
+
+impl Display for AtomicAbility {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.effect)
+    }
+}
+impl Debug for AtomicAbility {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{:?}", self.effect)
+    }
+}
+
+impl AtomicAbility {
+    pub fn ability(&self) -> &AtomicAbilityFn {
+        self.ability.deref()
+    }
+
+    pub fn effect(&self) -> &Effect {
+        &self.effect
+    }
+
+    pub fn choices_sources(&self) -> Option<&ChoicesSources> {
+        self.choices_sources.as_ref()
+    }
+
+    pub fn after_capacity(&self) -> Option<&AfterCapacity> {
+        self.after_capacity.as_ref()
+    }
+
+    pub fn min_choices(&self) -> usize {
+        self.min_choices
+    }
+
+    pub fn max_choices(&self) -> usize {
+        self.max_choices
+    }
+}
+
+impl ChoicesSources {
+    pub fn contains(&self, source: &ChoicesSources) -> bool {
+        match self {
+            ChoicesSources::And(a, b) => a.contains(source) || b.contains(source),
+            s => s == source
+        }
+    }
+}
+
+impl AfterCapacity {
+    pub fn contains(&self, capacity: &AfterCapacity) -> bool {
+        match self {
+            AfterCapacity::And(a, b) => a.contains(capacity) || b.contains(capacity),
+            c => c == capacity
+        }
+    }
+}
+#[derive(Debug, Clone)]
+pub enum Ability {
+    Atomic(AtomicAbility),
+    And(Box<Ability>, Box<Ability>),
+    Or(Box<Ability>, Box<Ability>),
+    Cond(Predicate, Box<Ability>),
+    Delayed(Box<Ability>),
+}
+
+impl Display for Ability {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Atomic(a) => { f.write_fmt(format_args!("{}", a.to_string())) }
+            Ability::And(a, b) => { f.write_fmt(format_args!("{} and {}", a, b)) }
+            Ability::Or(a, b) => { f.write_fmt(format_args!("{} or {}", a, b)) }
+            Ability::Cond(cond, a) => { f.write_fmt(format_args!("if {}, then {}", cond, a)) }
+            Delayed(d) => {f.write_fmt(format_args!("delayed {}", d)) }
+        }
+    }
+}
+
+impl Ability {
+    /// Tells if any atomic part of this ability carries the given after capacity
+    pub fn grants(&self, capacity: &AfterCapacity) -> bool {
+        match self {
+            Atomic(a) => a.after_capacity().is_some_and(|c| c.contains(capacity)),
+            Ability::And(a, b) | Ability::Or(a, b) => a.grants(capacity) || b.grants(capacity),
+            Ability::Cond(_, a) | Delayed(a) => a.grants(capacity),
+        }
+    }
+
+    /// Effects of the atomic parts of this ability, both options of a choice included
+    pub fn effects(&self) -> Vec<&Effect> {
+        match self {
+            Atomic(a) => vec![a.effect()],
+            Ability::And(a, b) | Ability::Or(a, b) => [a.effects(), b.effects()].concat(),
+            Ability::Cond(_, a) | Delayed(a) => a.effects(),
+        }
+    }
+
+    /// Expected total of `amount` over the effects, when each option of a choice is taken half
+    /// of the time and conditions hold
+    pub fn expected(&self, amount: fn(&Effect) -> i32) -> f32 {
+        match self {
+            Atomic(a) => amount(a.effect()) as f32,
+            Ability::And(a, b) => a.expected(amount) + b.expected(amount),
+            Ability::Or(a, b) => (a.expected(amount) + b.expected(amount)) / 2.0,
+            Ability::Cond(_, a) | Delayed(a) => a.expected(amount),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Abilities {
+    pub on_board: Option<Ability>,
+    pub on_faction: Option<Ability>,
+    pub on_scrap: Option<Ability>
+}
+
+#[derive(Clone, Debug)]
+pub struct AbilityFactory;
+
+impl AbilityFactory {
+    pub fn give_damages(amt: i32) -> Ability {
+        Atomic(AtomicAbility {
+            ability: Arc::new(AtomicAbilityFn::Default(Box::new(move |gs: &GameState| -> Result<GameState, RuleError> {
+                let mut new_player: Player = gs.get_current_player();
+                new_player.hand.damage += amt;
+                gs.mutate_players(new_player, gs.turn%2)
+            }))),
+            choices_sources: None,
+            after_capacity: None,
+            min_choices: 0,
+            max_choices: 0,
+            effect: Effect::Damage(amt),
+        })
+    }
+
+    pub fn give_trade(amt: i32) -> Ability {
+        Atomic(AtomicAbility {
+            ability: Arc::new(AtomicAbilityFn::Default(Box::new(move |gs: &GameState| -> Result<GameState, RuleError> {
+                let mut new_player: Player = gs.get_current_player();
+                new_player.hand.trade += amt;
+                gs.mutate_players(new_player, gs.turn%2)
+            }))),
+            choices_sources: None,
+            after_capacity: None,
+            min_choices: 0,
+            max_choices: 0,
+            effect: Effect::Trade(amt),
+        })
+    }
+
+    pub fn give_authority(amt: i32) -> Ability {
+        Atomic(AtomicAbility {
+            ability: Arc::new(AtomicAbilityFn::Default(Box::new(move |gs: &GameState| -> Result<GameState, RuleError> {
+                let mut new_player: Player = gs.get_current_player();
+                new_player.authority += amt;
+                let mut gs = gs.mutate_players(new_player, gs.turn%2)?;
+                gs.emit(Event::AuthorityGained { player: gs.turn%2, amount: amt });
+                Ok(gs)
+            }))),
+            choices_sources: None,
+            after_capacity: None,
+            min_choices: 0,
+            max_choices: 0,
+            effect: Effect::Authority(amt),
+        })
+    }
+
+    pub fn draw(amt: i32) -> Ability {
+        Atomic(AtomicAbility {
+            ability: Arc::new(AtomicAbilityFn::Default(Box::new(move |gs: &GameState| -> Result<GameState, RuleError> {
+                let mut gs = gs.clone();
+                gs.draw(gs.turn%2, amt as usize)?;
+                Ok(gs)
+            }))),
+            choices_sources: None,
+            after_capacity: None,
+            min_choices: 0,
+            max_choices: 0,
+            effect: Effect::Draw(amt),
+        })
+    }
+
+    pub fn next_ship_on_top() -> Ability {
+        Atomic(AtomicAbility {
+            ability: Arc::new(AtomicAbilityFn::Default(Box::new(|gs: &GameState| -> Result<GameState, RuleError> {
+                let mut current_player = gs.get_current_player();
+                current_player.hand.next_n_ships_on_top += 1;
+                gs.mutate_players(current_player, gs.turn%2)
+            }))),
+            choices_sources: None,
+            after_capacity: Some(NextShipOnTop),
+            min_choices: 0,
+            max_choices: 0,
+            effect: Effect::NextShipOnTop,
+        })
+    }
+
+    pub fn destroy_target_base() -> Ability {
+        Delayed(Box::new(Atomic(AtomicAbility {
+            ability: Arc::new(AtomicAbilityFn::Card(Box::new(move |gs: &GameState, card: &Card| -> Result<GameState, RuleError> {
+                let mut gs = gs.clone();
+                let opponent = (gs.turn+1)%2;
+                gs.transfer(*card, Zone::Played(opponent), Zone::Discard(opponent))?;
+                gs.emit(Event::BaseDestroyed { player: opponent, card: *card });
+                Ok(gs)
+            }))),
+            choices_sources: Some(EnemyBase),
+            after_capacity: None,
+            min_choices: 0,
+            max_choices: 1,
+            effect: Effect::DestroyBase,
+        })))
+    }
+
+    pub fn scrap_trade_row() -> Ability {
+        Delayed(Box::new(Atomic(AtomicAbility {
+            ability: Arc::new(AtomicAbilityFn::Card(Box::new(move |gs: &GameState, c: &Card| -> Result<GameState, RuleError> {
+                let mut gs = gs.clone();
+                let at = gs.trade_row.iter().position(|r| r == c).ok_or(RuleError::CardNotInZone("trade row"))?;
+                gs.scrap_at(Zone::TradeRow, at)?;
+                gs.refill_trade_row(at);
+                Ok(gs)
+            }))),
+            choices_sources: Some(TradeRow),
+            after_capacity: None,
+            min_choices: 0,
+            max_choices: 1,
+            effect: Effect::ScrapTradeRow,
+        })))
+    }
+
+    pub fn free_ship_on_top() -> Ability {
+        Atomic(AtomicAbility {
+            ability: Arc::new(AtomicAbilityFn::Default(Box::new(move |gs: &GameState| -> Result<GameState, RuleError> {
+                let mut current_player = gs.get_current_player();
+                current_player.hand.next_n_ships_on_top += 1;
+                current_player.hand.next_n_ships_free += 1;
+                gs.mutate_players(current_player, gs.turn%2)
+            }))),
+            choices_sources: None,
+            after_capacity: Some(AfterCapacity::And(Box::new(AfterCapacity::NextShipFree), Box::new(NextShipOnTop))),
+            min_choices: 0,
+            max_choices: 0,
+            effect: Effect::FreeShipOnTop,
+        })
+    }
+
+    pub fn draw_for_each(f: Faction) -> Ability {
+        let faction = f.clone();
+        Atomic(AtomicAbility {
+            ability: Arc::new(AtomicAbilityFn::Default(Box::new(move |gs: &GameState| -> Result<GameState, RuleError> {
+                let current_player = gs.get_current_player();
+                let nb_to_draw = (0..current_player.hand.played.len())
+                    .filter(|i| !current_player.hand.played[*i].is_base())
+                    .filter(|i| GameLogic::played_factions(&current_player, *i).contains(&faction))
+                    .count();
+                let mut gs = gs.clone();
+                gs.draw(gs.turn%2, nb_to_draw)?;
+                Ok(gs)
+            }))),
+            choices_sources: None,
+            after_capacity: None,
+            min_choices: 0,
+            max_choices: 0,
+            effect: Effect::DrawPerFaction(f),
+        })
+    }
+
+    pub fn target_discard() -> Ability {
+        Atomic(AtomicAbility {
+            ability: Arc::new(AtomicAbilityFn::Default(Box::new(|gs: &GameState| -> Result<GameState, RuleError> {
+                let mut opponent = gs.get_opponent_player();
+                opponent.hand.discards += 1;
+                gs.mutate_players(opponent, (gs.turn+1)%2)
+            }))),
+            choices_sources: None,
+            after_capacity: None,
+            min_choices: 0,
+            max_choices: 0,
+            effect: Effect::TargetDiscard,
+        })
+    }
+
+    pub fn discard() -> Ability {
+        Atomic(AtomicAbility {
+            ability: Arc::new(AtomicAbilityFn::Card(Box::new(|gs: &GameState, c: &Card| -> Result<GameState, RuleError> {
+                let mut gs = gs.clone();
+                let me = gs.turn%2;
+                gs.transfer(*c, Zone::Hand(me), Zone::Discard(me))?;
+                Ok(gs)
+            }))),
+            choices_sources: Some(Playable),
+            after_capacity: None,
+            min_choices: 1,
+            max_choices: 1,
+            effect: Effect::Discard,
+        })
+    }
+
+    pub fn discard_n_draw_n(max: i32) -> Ability {
+        Delayed(Box::new(Atomic(AtomicAbility {
+            ability: Arc::new(AtomicAbilityFn::Cards(Box::new(move |gs: &GameState, cs: &Vec<Card>| -> Result<GameState, RuleError> {
+                let mut gs = gs.clone();
+                let me = gs.turn%2;
+                for c in cs {
+                    gs.transfer(*c, Zone::Hand(me), Zone::Discard(me))?;
+                }
+                gs.draw(me, cs.len())?;
+                Ok(gs)
+            }))),
+            choices_sources: Some(Playable),
+            after_capacity: None,
+            min_choices: 0,
+            max_choices: max as usize,
+            effect: Effect::DiscardThenDraw { max },
+        })))
+    }
+
+    pub fn all_ships_get(n: i32) -> Ability {
+        Atomic(AtomicAbility {
+            ability: Arc::new(AtomicAbilityFn::Default(Box::new(move |gs: &GameState| -> Result<GameState, RuleError> {
+                let mut current_player = gs.get_current_player();
+                let nb_ships = current_player.hand.get_played_ships().len() as i32;
+                current_player.hand.damage += n * nb_ships;
+                current_player.hand.damage_per_ship += n;
+                gs.mutate_players(current_player, gs.turn%2)
+            }))),
+            choices_sources: None,
+            after_capacity: None,
+            min_choices: 0,
+            max_choices: 0,
+            effect: Effect::ShipsGetDamage(n),
+        })
+    }
+
+    pub fn scrap_at_most(n: i32) -> Ability {
+        Atomic(AtomicAbility {
+            ability: Arc::new(AtomicAbilityFn::CardsFromHandOrDiscard(Box::new(move |gs: &GameState, hand_cards: &Vec<Card>, discard_cards: &Vec<Card>| -> Result<GameState, RuleError> {
+                let mut gs = gs.clone();
+                let me = gs.turn%2;
+                for card in hand_cards {
+                    gs.scrap(*card, Zone::Hand(me))?;
+                }
+                for card in discard_cards {
+                    gs.scrap(*card, Zone::Discard(me))?;
+                }
+                Ok(gs)
+            }))),
+            choices_sources: Some(ChoicesSources::And(Box::new(Playable), Box::new(Discarded))),
+            after_capacity: None,
+            min_choices: 0,
+            max_choices: n as usize,
+            effect: Effect::ScrapHandOrDiscard { max: n },
+        })
+    }
+
+    pub fn copy_ship() -> Ability {
+        Atomic(AtomicAbility {
+            ability: Arc::new(AtomicAbilityFn::Card(Box::new(|gs: &GameState, c: &Card| -> Result<GameState, RuleError> {
+                let mut current_player = gs.get_current_player();
+                current_player.hand.copied = Some(*c);
+                let gs = gs.mutate_players(current_player, gs.turn%2)?;
+                match c.get_abilities().on_board.as_ref() {
+                    Some(a) => GameLogic::resolve(&gs, a),
+                    None => Ok(gs),
+                }
+            }))),
+            choices_sources: Some(Played),
+            after_capacity: None,
+            min_choices: 0,
+            max_choices: 1,
+            effect: Effect::CopyShip,
+        })
+    }
+
+    pub fn ally_to_all() -> Ability {
+        Atomic(AtomicAbility {
+            ability: Arc::new(AtomicAbilityFn::Default(Box::new(move |gs: &GameState| -> Result<GameState, RuleError> {
+                Ok(gs.clone())
+            }))),
+            choices_sources: None,
+            after_capacity: Some(AllyToAll),
+            min_choices: 0,
+            max_choices: 0,
+            effect: Effect::AllyToAll,
+        })
+    }
+
+    pub fn scrap_n_draw_n(max: i32) -> Ability {
+        Delayed(Box::new(Atomic(AtomicAbility {
+            ability: Arc::new(AtomicAbilityFn::CardsFromHandOrDiscard(Box::new(move |gs: &GameState, h: &Vec<Card>, d: &Vec<Card>| -> Result<GameState, RuleError> {
+                let mut gs = gs.clone();
+                let me = gs.turn%2;
+                for c in h {
+                    gs.scrap(*c, Zone::Hand(me))?;
+                }
+                for c in d {
+                    gs.scrap(*c, Zone::Discard(me))?;
+                }
+                gs.draw(me, h.len() + d.len())?;
+                Ok(gs)
+
+            }))),
+            choices_sources: Some(ChoicesSources::And(Box::new(Playable), Box::new(Discarded))),
+            after_capacity: None,
+            min_choices: 0,
+            max_choices: max as usize,
+            effect: Effect::ScrapThenDraw { max },
+        })))
+    }
+
+    pub fn scrap_from_hand() -> Ability {
+        Atomic(AtomicAbility {
+            ability: Arc::new(AtomicAbilityFn::Cards(Box::new(|gs: &GameState, cs: &Vec<Card>| -> Result<GameState, RuleError> {
+                let mut gs = gs.clone();
+                let me = gs.turn%2;
+                for c in cs {
+                    gs.scrap(*c, Zone::Hand(me))?;
+                }
+                Ok(gs)
+            }))),
+            choices_sources: Some(Playable),
+            after_capacity: None,
+            min_choices: 1,
+            max_choices: 1,
+            effect: Effect::ScrapFromHand,
+        })
+    }
+
+    pub fn draw_then_scrap() -> Ability {
+        Delayed(Box::new(Atomic(AtomicAbility {
+            ability: Arc::new(AtomicAbilityFn::Default(Box::new(|gs: &GameState| {
+                let mut gs = gs.clone();
+                gs.draw(gs.turn%2, 1)?;
+                Ok(gs)
+            }))),
+            choices_sources: None,
+            after_capacity: Some(ScrapFromHand),
+            min_choices: 0,
+            max_choices: 0,
+            effect: Effect::DrawThenScrap,
+        })))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_predicate() {
+        let mut gs = GameState::new();
+        let p1_2_bases_pred = Predicate {
+            description: "Test si joueur 2 a au moins 2 bases en jeu.".to_string(),
+            pred: |gs: &GameState| {
+                gs.players.1.hand.get_played_bases().len() >= 2
+            },
+        };
+        assert!(!p1_2_bases_pred.test(&gs));
+        gs.players.1.hand.played.push(Card::TheHive);
+        gs.players.1.hand.played.push(Card::MechWorld);
+        assert!(p1_2_bases_pred.test(&gs));
+    }
+
+    #[test]
+    fn test_effects() {
+        let cutter = Card::Cutter.get_abilities();
+        let on_board = cutter.on_board.as_ref().unwrap();
+        assert_eq!(on_board.effects(), vec![&Effect::Authority(4), &Effect::Trade(2)]);
+        assert_eq!(on_board.to_string(), "Gain 4 authority and Add 2 trade");
+        assert_eq!(cutter.on_faction.as_ref().unwrap().effects(), vec![&Effect::Damage(4)]);
+
+        let trading_post = Card::TradingPost.get_abilities().on_board.as_ref().unwrap();
+        assert_eq!(trading_post.expected(Effect::trade), 0.5);
+        assert_eq!(trading_post.expected(Effect::authority), 0.5);
+        let yacht = Card::EmbassyYacht.get_abilities().on_board.as_ref().unwrap();
+        assert_eq!(yacht.expected(Effect::draws), 2.0);
+        assert!(yacht.to_string().ends_with("if you have two or more bases in play, then Draw 2 cards"));
+
+        let brain_world = Card::BrainWorld.get_abilities().on_board.as_ref().unwrap();
+        assert_eq!(brain_world.effects(), vec![&Effect::ScrapThenDraw { max: 2 }]);
+    }
+}
@@ -0,0 +1,207 @@
+use rand::prelude::{SliceRandom, StdRng};
+use rand::{Rng, SeedableRng};
+use crate::gamelogic::{Action, GameLogic};
+use crate::error::RuleError;
+use crate::star_realms::GameState;
+
+/// Anything able to pick the next action of the current player
+pub trait Agent {
+    fn name(&self) -> &str;
+    fn act(&mut self, gs: &GameState) -> Action;
+}
+
+/// Plays uniformly among the legal actions
+pub struct RandomAgent {
+    rng: StdRng,
+}
+
+impl RandomAgent {
+    pub fn new(seed: u64) -> RandomAgent {
+        RandomAgent {
+            rng: StdRng::seed_from_u64(seed),
+        }
+    }
+}
+
+impl Agent for RandomAgent {
+    fn name(&self) -> &str {
+        "random"
+    }
+
+    fn act(&mut self, gs: &GameState) -> Action {
+        *GameLogic::legal_actions(gs).choose(&mut self.rng).unwrap_or(&Action::EndTurn)
+    }
+}
+
+/// Plays every card, buys the most expensive card it can afford and attacks with everything
+pub struct GreedyAgent;
+
+impl GreedyAgent {
+    /// Rough value of a state for `player_id`
+    pub fn evaluate(gs: &GameState, player_id: i32) -> Result<f32, RuleError> {
+        let me = gs.get_player(player_id)?;
+        let opponent = gs.get_player(1 - player_id)?;
+        let owned = |p: &crate::star_realms::Player| -> f32 {
+            p.deck.iter()
+                .chain(p.discard.iter())
+                .chain(p.hand.playable.iter())
+                .chain(p.hand.played.iter())
+                .map(|c| c.get_cost().unwrap_or(0) as f32)
+                .sum()
+        };
+        Ok((me.authority - opponent.authority) as f32
+            + me.hand.trade as f32 * 0.5
+            + me.hand.damage as f32
+            + me.hand.playable.len() as f32
+            + owned(me) * 0.2
+            - owned(opponent) * 0.2)
+    }
+}
+
+impl Agent for GreedyAgent {
+    fn name(&self) -> &str {
+        "greedy"
+    }
+
+    fn act(&mut self, gs: &GameState) -> Action {
+        let actions = GameLogic::legal_actions(gs);
+        let me = gs.turn % 2;
+        if !gs.pending.is_empty() {
+            let score = |a: &Action| GameLogic::apply(gs, a).and_then(|next| Self::evaluate(&next, me)).unwrap_or(f32::MIN);
+            return actions.iter()
+                .copied()
+                .max_by(|a, b| score(a).total_cmp(&score(b)))
+                .unwrap_or(Action::Decline);
+        }
+        let first = |f: &dyn Fn(&Action) -> bool| actions.iter().copied().find(|a| f(a));
+        if let Some(a) = first(&|a| matches!(a, Action::Play(_) | Action::Ally(_) | Action::Activate(_))) {
+            return a;
+        }
+        if let Some(a) = first(&|a| matches!(a, Action::AttackBase(_))) {
+            return a;
+        }
+        if actions.contains(&Action::AttackPlayer) {
+            return Action::AttackPlayer;
+        }
+        let best_buy = actions.iter()
+            .filter_map(|a| match a {
+                Action::Buy(i) => gs.trade_row[*i].get_cost().map(|c| (c, *a)),
+                _ => None,
+            })
+            .max_by_key(|(c, _)| *c);
+        if let Some((_, a)) = best_buy {
+            return a;
+        }
+        if actions.contains(&Action::BuyExplorer) {
+            return Action::BuyExplorer;
+        }
+        Action::EndTurn
+    }
+}
+
+/// Flat Monte Carlo search over the legal actions.
+///
+/// Every playout tries one action on a copy of the game where the hidden cards are shuffled again,
+/// then lets greedy players go on until the end of the opponent's next turn.
+/// Actions are picked for playouts with UCB1 and the most tried one is played.
+pub struct MonteCarloAgent {
+    rng: StdRng,
+    playouts: usize,
+}
+
+impl MonteCarloAgent {
+    pub const DEFAULT_PLAYOUTS: usize = 100;
+    const MAX_PLAYOUT_ACTIONS: usize = 300;
+
+    /// `playouts` is the search budget of each decision
+    pub fn new(seed: u64, playouts: usize) -> MonteCarloAgent {
+        MonteCarloAgent {
+            rng: StdRng::seed_from_u64(seed),
+            playouts,
+        }
+    }
+
+    /// Outcome of one playout for `me`, between 0 (lost) and 1 (won)
+    fn playout(&mut self, gs: &GameState, action: &Action, me: i32) -> f32 {
+        let horizon = gs.turn + 2;
+        let mut state = match GameLogic::apply(&gs.determinize(self.rng.gen()), action) {
+            Ok(state) => state,
+            Err(_) => return 0.,
+        };
+        let mut greedy = GreedyAgent;
+        for _ in 0..Self::MAX_PLAYOUT_ACTIONS {
+            if state.is_over() || state.turn >= horizon {
+                break;
+            }
+            match GameLogic::apply(&state, &greedy.act(&state)) {
+                Ok(next) => state = next,
+                Err(_) => break,
+            }
+        }
+        match state.winner() {
+            Some(winner) => if winner == me { 1. } else { 0. },
+            None => GreedyAgent::evaluate(&state, me).map_or(0.5, |value| 1. / (1. + (-value / 10.).exp())),
+        }
+    }
+}
+
+impl Agent for MonteCarloAgent {
+    fn name(&self) -> &str {
+        "montecarlo"
+    }
+
+    fn act(&mut self, gs: &GameState) -> Action {
+        let actions = GameLogic::legal_actions(gs);
+        if actions.len() < 2 {
+            return actions.first().copied().unwrap_or(Action::EndTurn);
+        }
+        let me = gs.turn % 2;
+        let mut visits = vec![0usize; actions.len()];
+        let mut totals = vec![0f32; actions.len()];
+        for n in 0..self.playouts.max(actions.len()) {
+            let i = match visits.iter().position(|v| *v == 0) {
+                Some(i) => i,
+                None => {
+                    let ucb = |i: usize| totals[i] / visits[i] as f32
+                        + (2. * (n as f32).ln() / visits[i] as f32).sqrt();
+                    (0..actions.len()).max_by(|a, b| ucb(*a).total_cmp(&ucb(*b))).unwrap()
+                }
+            };
+            totals[i] += self.playout(gs, &actions[i], me);
+            visits[i] += 1;
+        }
+        let best = (0..actions.len()).max_by_key(|i| visits[*i]).unwrap();
+        actions[best]
+    }
+}
+
+/// Names accepted by `from_name`
+pub const AGENT_NAMES: [&str; 3] = ["random", "greedy", "montecarlo"];
+
+/// Builds one of the baseline agents from its name
+pub fn from_name(name: &str, seed: u64) -> Option<Box<dyn Agent>> {
+    match name {
+        "random" => Some(Box::new(RandomAgent::new(seed))),
+        "greedy" => Some(Box::new(GreedyAgent)),
+        "montecarlo" => Some(Box::new(MonteCarloAgent::new(seed, MonteCarloAgent::DEFAULT_PLAYOUTS))),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_montecarlo_plays_legal_actions() {
+        let mut gs = GameState::with_seed(8);
+        let mut agent = MonteCarloAgent::new(8, 10);
+        while gs.turn < 3 {
+            let sample = gs.determinize(1);
+            let count = |g: &GameState| g.players.1.deck.len() + g.players.1.hand.playable.len();
+            assert_eq!(count(&sample), count(&gs));
+            assert_eq!(sample.trade_deck.len(), gs.trade_deck.len());
+            gs = GameLogic::apply(&gs, &agent.act(&gs)).unwrap();
+        }
+    }
+}
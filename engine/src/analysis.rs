@@ -0,0 +1,170 @@
+use crate::abilities::Effect;
+use crate::cards::{Card, Faction};
+use crate::star_realms::Player;
+
+/// Cards drawn at the end of a turn
+pub const HAND_SIZE: usize = 5;
+
+/// What a player can expect from their next hand.
+///
+/// The next hand is drawn at the end of the player's next turn, once the cards of their hand and
+/// their ships in play went to the discard pile: it takes the top of the deck, then cards of the
+/// shuffled discard pile when the deck runs out. Draws from abilities and cards acquired before it
+/// are left out, so the order of the deck being hidden, the hand follows a hypergeometric
+/// distribution over the deck and the discard pile.
+pub struct Analysis<'a> {
+    player: &'a Player,
+    /// Cards that will be shuffled into the deck when it runs out
+    discard: Vec<Card>,
+}
+
+impl<'a> Analysis<'a> {
+    pub fn new(player: &'a Player) -> Analysis<'a> {
+        let discard = player.discard.iter()
+            .chain(player.hand.playable.iter())
+            .chain(player.hand.played.iter().filter(|c| !c.is_base()))
+            .copied()
+            .collect();
+        Analysis { player, discard }
+    }
+
+    /// Cards of the next hand taken from the deck and from the discard pile
+    fn draws(&self) -> (usize, usize) {
+        let from_deck = self.player.deck.len().min(HAND_SIZE);
+        (from_deck, (HAND_SIZE - from_deck).min(self.discard.len()))
+    }
+
+    /// Chances of each card of the deck and of the discard pile to be in the next hand
+    fn chances(&self) -> (f64, f64) {
+        let (from_deck, from_discard) = self.draws();
+        let chance = |drawn: usize, of: usize| if of == 0 { 0.0 } else { drawn as f64 / of as f64 };
+        (chance(from_deck, self.player.deck.len()), chance(from_discard, self.discard.len()))
+    }
+
+    /// Number of cards the player owns for each faction, `None` for cards without faction
+    pub fn composition(&self) -> Vec<(Option<Faction>, usize)> {
+        let owned: Vec<Card> = self.player.deck.iter().chain(self.discard.iter())
+            .chain(self.player.hand.played.iter().filter(|c| c.is_base()))
+            .copied()
+            .collect();
+        [None, Some(Faction::Blob), Some(Faction::Machine), Some(Faction::Star), Some(Faction::Trade)]
+            .into_iter()
+            .map(|f| {
+                let count = owned.iter().filter(|c| c.get_faction() == f).count();
+                (f, count)
+            })
+            .collect()
+    }
+
+    /// Expected total of `amount` over the abilities played from the next hand, without ally abilities
+    pub fn expected(&self, amount: fn(&Effect) -> i32) -> f64 {
+        let (deck_chance, discard_chance) = self.chances();
+        let value = |c: &Card| c.get_abilities().on_board.as_ref().map_or(0.0, |a| a.expected(amount) as f64);
+        self.player.deck.iter().map(value).sum::<f64>() * deck_chance
+            + self.discard.iter().map(value).sum::<f64>() * discard_chance
+    }
+
+    pub fn expected_trade(&self) -> f64 {
+        self.expected(Effect::trade)
+    }
+
+    pub fn expected_damage(&self) -> f64 {
+        self.expected(Effect::damage)
+    }
+
+    pub fn expected_authority(&self) -> f64 {
+        self.expected(Effect::authority)
+    }
+
+    /// Probability of at least `at_least` cards matching `filter` in the next hand
+    pub fn probability(&self, filter: impl Fn(&Card) -> bool, at_least: usize) -> f64 {
+        let (from_deck, from_discard) = self.draws();
+        let in_deck = self.player.deck.iter().filter(|c| filter(c)).count();
+        let in_discard = self.discard.iter().filter(|c| filter(c)).count();
+        // the deck only holds the hand when it has enough cards, otherwise all of it is drawn
+        if self.player.deck.len() >= HAND_SIZE {
+            at_least_hypergeometric(self.player.deck.len(), in_deck, from_deck, at_least)
+        } else {
+            at_least_hypergeometric(self.discard.len(), in_discard, from_discard, at_least.saturating_sub(in_deck))
+        }
+    }
+
+    /// Probability of drawing at least one `card` in the next hand
+    pub fn card_probability(&self, card: Card) -> f64 {
+        self.probability(|c| *c == card, 1)
+    }
+
+    /// Probability of drawing at least two cards of `faction` in the next hand, so that they are allies
+    pub fn ally_probability(&self, faction: &Faction) -> f64 {
+        self.probability(|c| c.get_faction().as_ref() == Some(faction), 2)
+    }
+
+    /// Number of hands drawn from the deck alone before the discard pile is shuffled into it
+    pub fn turns_until_reshuffle(&self) -> usize {
+        self.player.deck.len() / HAND_SIZE
+    }
+}
+
+impl Player {
+    pub fn analysis(&self) -> Analysis<'_> {
+        Analysis::new(self)
+    }
+}
+
+fn binomial(n: usize, k: usize) -> f64 {
+    if k > n {
+        return 0.0;
+    }
+    (0..k.min(n - k)).fold(1.0, |c, i| c * (n - i) as f64 / (i + 1) as f64)
+}
+
+/// Probability of at least `at_least` successes in `draws` draws without replacement from
+/// `population` items of which `successes` are successes
+pub fn at_least_hypergeometric(population: usize, successes: usize, draws: usize, at_least: usize) -> f64 {
+    if at_least == 0 {
+        return 1.0;
+    }
+    let total = binomial(population, draws);
+    if total == 0.0 {
+        return 0.0;
+    }
+    (at_least..=draws.min(successes))
+        .map(|k| binomial(successes, k) * binomial(population - successes, draws - k) / total)
+        .sum()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn close(a: f64, b: f64) -> bool {
+        (a - b).abs() < 1e-9
+    }
+
+    #[test]
+    fn test_hypergeometric() {
+        // one Viper among the 10 cards of a starting deck: drawn half of the time
+        assert!(close(at_least_hypergeometric(10, 1, 5, 1), 0.5));
+        assert!(close(at_least_hypergeometric(10, 2, 5, 2), 2.0 / 9.0));
+        assert!(close(at_least_hypergeometric(10, 0, 5, 1), 0.0));
+        assert!(close(at_least_hypergeometric(3, 3, 3, 3), 1.0));
+    }
+
+    #[test]
+    fn test_next_hand() {
+        let mut player = Player::new();
+        player.deck = vec![Card::Scout, Card::Scout, Card::Viper];
+        player.discard = vec![Card::Cutter, Card::Cutter, Card::Scout, Card::Viper];
+        player.hand.playable = vec![];
+        let analysis = player.analysis();
+        // the 3 cards of the deck, then 2 of the 4 of the discard pile
+        assert!(close(analysis.expected_trade(), 2.0 + (2.0 + 2.0 + 1.0) / 2.0));
+        assert!(close(analysis.expected_authority(), 4.0));
+        assert!(close(analysis.card_probability(Card::Viper), 1.0));
+        assert!(close(analysis.card_probability(Card::Cutter), 5.0 / 6.0));
+        assert!(close(analysis.ally_probability(&Faction::Trade), 1.0 / 6.0));
+        assert_eq!(analysis.turns_until_reshuffle(), 0);
+        assert_eq!(analysis.composition()[0], (None, 5));
+        assert_eq!(analysis.composition()[4], (Some(Faction::Trade), 2));
+    }
+}
@@ -1,11 +1,11 @@
 use std::fmt::{Display, Formatter};
-use std::rc::Rc;
+use std::sync::LazyLock;
 use crate::abilities::{Abilities, AbilityFactory, Predicate};
 use crate::abilities::Ability::{And, Cond, Or};
 use crate::cards::Faction::{Blob, Machine, Star, Trade};
 use crate::star_realms::GameState;
 
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub enum Faction {
     Blob,
     Machine,
@@ -30,70 +30,158 @@ pub struct BasicCard {
     pub abilities: Abilities,
 }
 
+/// What a kind of card is and does: a tree of properties around its name and abilities
 #[derive(Debug, Clone)]
-pub enum Card {
+pub enum CardDef {
     Basic(BasicCard),
-    Faction(Box<Card>, Faction),
-    Cost(Box<Card>, i32),
-    Base(Box<Card>, i32, bool),
+    Faction(Box<CardDef>, Faction),
+    Cost(Box<CardDef>, i32),
+    Base(Box<CardDef>, i32, bool),
 }
 
-impl Card {
+impl CardDef {
     pub fn get_name(&self) -> String {
         match self {
-            Card::Basic(c) => { c.name.clone() }
-            Card::Faction(c, _) => { c.get_name() }
-            Card::Cost(c, _) => { c.get_name() }
-            Card::Base(c, _, _) => { c.get_name() }
+            CardDef::Basic(c) => { c.name.clone() }
+            CardDef::Faction(c, _) => { c.get_name() }
+            CardDef::Cost(c, _) => { c.get_name() }
+            CardDef::Base(c, _, _) => { c.get_name() }
         }
     }
 
     pub fn get_faction(&self) -> Option<Faction> {
         match self {
-            Card::Basic(_) => { None }
-            Card::Faction(_, f) => { Some(f.clone()) }
-            Card::Cost(c, _) => { c.get_faction() }
-            Card::Base(c, _, _) => { c.get_faction() }
+            CardDef::Basic(_) => { None }
+            CardDef::Faction(_, f) => { Some(f.clone()) }
+            CardDef::Cost(c, _) => { c.get_faction() }
+            CardDef::Base(c, _, _) => { c.get_faction() }
         }
     }
 
     pub fn get_cost(&self) -> Option<i32> {
         match self {
-            Card::Basic(_) => { None }
-            Card::Faction(c, _) => { c.get_cost() }
-            Card::Cost(_, c) => { Some(*c) }
-            Card::Base(c, _, _) => { c.get_cost() }
+            CardDef::Basic(_) => { None }
+            CardDef::Faction(c, _) => { c.get_cost() }
+            CardDef::Cost(_, c) => { Some(*c) }
+            CardDef::Base(c, _, _) => { c.get_cost() }
         }
     }
 
-    pub fn get_abilities(&self) -> Abilities {
+    pub fn get_abilities(&self) -> &Abilities {
         match self {
-            Card::Basic(c) => { c.abilities.clone() }
-            Card::Faction(c, _) => { c.get_abilities() }
-            Card::Cost(c, _) => { c.get_abilities() }
-            Card::Base(c, _, _) => { c.get_abilities() }
+            CardDef::Basic(c) => { &c.abilities }
+            CardDef::Faction(c, _) => { c.get_abilities() }
+            CardDef::Cost(c, _) => { c.get_abilities() }
+            CardDef::Base(c, _, _) => { c.get_abilities() }
         }
     }
 
-    pub fn filter_ships(from: Vec<Card>) -> Vec<Card> {
-        let mut sink = from.clone();
-        for i in (0..from.len()).rev() {
-            if let Card::Base(..) = sink[i].clone() {
-                sink.remove(i);
-            }
+    pub fn get_defense(&self) -> Option<i32> {
+        match self {
+            CardDef::Base(_, d, _) => { Some(*d) }
+            _ => { None }
         }
-        sink
+    }
+
+    pub fn is_base(&self) -> bool {
+        matches!(self, CardDef::Base(..))
+    }
+
+    pub fn is_outpost(&self) -> bool {
+        matches!(self, CardDef::Base(_, _, true))
+    }
+}
+
+/// A kind of card. Cards are plain ids so that game states copy cheaply: what a card is and
+/// does is looked up in a static table of `CardDef` built by `CardFactory::definition`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum Card {
+    Scout, Viper, Explorer,
+    // Trade Federation
+    FederationShuttle, Cutter, EmbassyYacht, Freighter,
+    CommandShip, TradeEscort, Flagship, TradingPost,
+    BarterWorld, DefenseCenter, CentralOffice, PortOfCall,
+    // Blob
+    BlobFighter, TradePod, BattlePod, Ram,
+    BlobDestroyer, BattleBlob, BlobCarrier, Mothership,
+    BlobWheel, TheHive, BlobWorld,
+    // Star Empire
+    ImperialFighter, ImperialFrigate, SurveyShip, Corvette,
+    Battlecruiser, Dreadnaught, SpaceStation, RecyclingStation,
+    WarWorld, RoyalRedoubt, FleetHq,
+    // Machine Cult
+    TradeBot, MissileBot, SupplyBot, BattleStation,
+    PatrolMech, StealthNeedle, BattleMech, MissileMech,
+    MechWorld, BrainWorld, MachineBase, Junkyard,
+}
+
+static DEFINITIONS: LazyLock<Vec<CardDef>> = LazyLock::new(|| Card::ALL.into_iter().map(CardFactory::definition).collect());
+
+impl Card {
+    /// Every kind of card, in the order of `CardFactory::catalog`
+    pub const ALL: [Card; 49] = [
+        Card::Scout, Card::Viper, Card::Explorer,
+        // Trade Federation
+        Card::FederationShuttle, Card::Cutter, Card::EmbassyYacht, Card::Freighter,
+        Card::CommandShip, Card::TradeEscort, Card::Flagship, Card::TradingPost,
+        Card::BarterWorld, Card::DefenseCenter, Card::CentralOffice, Card::PortOfCall,
+        // Blob
+        Card::BlobFighter, Card::TradePod, Card::BattlePod, Card::Ram,
+        Card::BlobDestroyer, Card::BattleBlob, Card::BlobCarrier, Card::Mothership,
+        Card::BlobWheel, Card::TheHive, Card::BlobWorld,
+        // Star Empire
+        Card::ImperialFighter, Card::ImperialFrigate, Card::SurveyShip, Card::Corvette,
+        Card::Battlecruiser, Card::Dreadnaught, Card::SpaceStation, Card::RecyclingStation,
+        Card::WarWorld, Card::RoyalRedoubt, Card::FleetHq,
+        // Machine Cult
+        Card::TradeBot, Card::MissileBot, Card::SupplyBot, Card::BattleStation,
+        Card::PatrolMech, Card::StealthNeedle, Card::BattleMech, Card::MissileMech,
+        Card::MechWorld, Card::BrainWorld, Card::MachineBase, Card::Junkyard,
+    ];
+
+    /// Position in `Card::ALL`
+    pub fn index(self) -> usize {
+        self as usize
+    }
+
+    fn definition(self) -> &'static CardDef {
+        &DEFINITIONS[self.index()]
+    }
+
+    pub fn get_name(&self) -> String {
+        self.definition().get_name()
+    }
+
+    pub fn get_faction(&self) -> Option<Faction> {
+        self.definition().get_faction()
+    }
+
+    pub fn get_cost(&self) -> Option<i32> {
+        self.definition().get_cost()
+    }
+
+    pub fn get_abilities(&self) -> &'static Abilities {
+        self.definition().get_abilities()
+    }
+
+    pub fn get_defense(&self) -> Option<i32> {
+        self.definition().get_defense()
+    }
+
+    pub fn is_base(&self) -> bool {
+        self.definition().is_base()
+    }
+
+    pub fn is_outpost(&self) -> bool {
+        self.definition().is_outpost()
+    }
+
+    pub fn filter_ships(from: Vec<Card>) -> Vec<Card> {
+        from.into_iter().filter(|c| !c.is_base()).collect()
     }
 
     pub fn filter_bases(from: Vec<Card>) -> Vec<Card> {
-        let mut sink = vec![];
-        for i in 0..from.len(){
-            let c = from[i].clone();
-            if let Card::Base(..) = c {
-                sink.push(c);
-            }
-        }
-        sink
+        from.into_iter().filter(|c| c.is_base()).collect()
     }
 }
 
@@ -101,37 +189,37 @@ impl Card {
 pub struct CardFactory;
 
 impl CardFactory {
-    fn basic(name: String, abilities: Abilities) -> Card {
-        Card::Basic(BasicCard {
+    fn basic(name: String, abilities: Abilities) -> CardDef {
+        CardDef::Basic(BasicCard {
             name,
             abilities,
         })
     }
 
-    fn cost(name: String, abilities: Abilities, cost: i32) -> Card {
-        Card::Cost(Box::new(Self::basic(name, abilities)), cost)
+    fn cost(name: String, abilities: Abilities, cost: i32) -> CardDef {
+        CardDef::Cost(Box::new(Self::basic(name, abilities)), cost)
     }
 
-    fn faction(name: String, abilities: Abilities, faction: Faction, cost: Option<i32>) -> Card {
+    fn faction(name: String, abilities: Abilities, faction: Faction, cost: Option<i32>) -> CardDef {
         match cost {
-            None => Card::Faction(Box::new(Self::basic(name, abilities)), faction),
-            Some(cost) => Card::Faction(Box::new(Self::cost(name, abilities, cost)), faction)
+            None => CardDef::Faction(Box::new(Self::basic(name, abilities)), faction),
+            Some(cost) => CardDef::Faction(Box::new(Self::cost(name, abilities, cost)), faction)
         }
     }
 
-    fn base(name: String, abilities: Abilities, faction: Option<Faction>, cost: Option<i32>, authority: i32, outpost: bool ) -> Card {
+    fn base(name: String, abilities: Abilities, faction: Option<Faction>, cost: Option<i32>, authority: i32, outpost: bool ) -> CardDef {
         match faction {
-            Some(faction) => Card::Base(
+            Some(faction) => CardDef::Base(
                 Box::new(Self::faction(name, abilities, faction, cost)),
                 authority,
                 outpost
             ),
-            None => Card::Base(Box::new(Self::basic(name, abilities)), authority, outpost)
+            None => CardDef::Base(Box::new(Self::basic(name, abilities)), authority, outpost)
         }
     }
 
     // Basic cards
-    pub fn viper() -> Card {
+    fn viper() -> CardDef {
         Self::basic("Viper".to_string(), Abilities {
             on_board: Some(AbilityFactory::give_damages(1)),
             on_faction: None,
@@ -139,7 +227,7 @@ impl CardFactory {
         })
     }
 
-    pub fn scout() -> Card {
+    fn scout() -> CardDef {
         Self::basic("Scout".to_string(), Abilities {
             on_board: Some(AbilityFactory::give_trade(1)),
             on_faction: None,
@@ -147,17 +235,17 @@ impl CardFactory {
         })
     }
 
-    pub fn explorer() -> Card {
+    fn explorer() -> CardDef {
         Self::cost("Explorer".to_string(), Abilities {
             on_board: Some(AbilityFactory::give_trade(2)),
             on_faction: None,
-            on_scrap: None,
+            on_scrap: Some(AbilityFactory::give_damages(2)),
         }, 2)
     }
 
     // Faction cards (base game)
     // Trade Federation
-    pub fn federation_shuttle() -> Card {
+    fn federation_shuttle() -> CardDef {
         Self::faction("Federation Shuttle".to_string(), Abilities {
             on_board: Some(AbilityFactory::give_trade(2)),
             on_faction: Some(AbilityFactory::give_authority(4)),
@@ -165,15 +253,15 @@ impl CardFactory {
         }, Trade, Some(1))
     }
 
-    pub fn cutter() -> Card {
+    fn cutter() -> CardDef {
         Self::faction("Cutter".to_string(), Abilities {
             on_board: Some(And(Box::new(AbilityFactory::give_authority(4)), Box::new(AbilityFactory::give_trade(2)))),
-            on_faction: Some(AbilityFactory::give_trade(4)),
+            on_faction: Some(AbilityFactory::give_damages(4)),
             on_scrap: None,
         }, Trade, Some(2))
     }
 
-    pub fn embassy_yacht() -> Card {
+    fn embassy_yacht() -> CardDef {
         Self::faction("Embassy Yacht".to_string(), Abilities {
             on_board: Some(And(
                 Box::new(AbilityFactory::give_authority(3)),
@@ -181,12 +269,8 @@ impl CardFactory {
                     Box::new(AbilityFactory::give_trade(2)),
                     Box::new(Cond(
                         Predicate::new(
-                            "If you have two or more bases in play, draw two cards".to_string(),
-                            Rc::new(|gs: &GameState| {
-                                let p = gs.get_current_player();
-                                let played_bases = p.hand.get_played_bases();
-                                played_bases.len() >= 2
-                            })
+                            "you have two or more bases in play".to_string(),
+                            |gs: &GameState| gs.current_player().hand.get_played_bases().len() >= 2
                         ),
                         Box::new(AbilityFactory::draw(2))
                     ))
@@ -197,7 +281,7 @@ impl CardFactory {
         }, Trade, Some(3))
     }
 
-    pub fn freighter() -> Card {
+    fn freighter() -> CardDef {
         Self::faction("Freighter".to_string(), Abilities {
             on_board: Some(AbilityFactory::give_trade(4)),
             on_faction: Some(AbilityFactory::next_ship_on_top()),
@@ -205,7 +289,7 @@ impl CardFactory {
         }, Trade, Some(4))
     }
 
-    pub fn command_ship() -> Card {
+    fn command_ship() -> CardDef {
         Self::faction("Command Ship".to_string(), Abilities {
             on_board: Some(And(
                 Box::new(AbilityFactory::give_authority(4)),
@@ -219,7 +303,7 @@ impl CardFactory {
         }, Trade, Some(8))
     }
 
-    pub fn trade_escort() -> Card {
+    fn trade_escort() -> CardDef {
         Self::faction("Trade Escort".to_string(), Abilities {
             on_board: Some(And(
                 Box::new(AbilityFactory::give_authority(4)),
@@ -230,7 +314,7 @@ impl CardFactory {
         }, Trade, Some(5))
     }
 
-    pub fn flagship() -> Card {
+    fn flagship() -> CardDef {
         Self::faction("Flagship".to_string(), Abilities {
             on_board: Some(And(
                 Box::new(AbilityFactory::give_damages(5)),
@@ -241,15 +325,15 @@ impl CardFactory {
         }, Trade, Some(6))
     }
 
-    pub fn trading_post() -> Card {
+    fn trading_post() -> CardDef {
         Self::base("Trading Post".to_string(), Abilities {
             on_board: Some(Or(Box::new(AbilityFactory::give_authority(1)), Box::new(AbilityFactory::give_trade(1)))),
             on_faction: None,
             on_scrap: Some(AbilityFactory::give_damages(3))
-        }, Some(Trade), Some(6), 4, true)
+        }, Some(Trade), Some(3), 4, true)
     }
 
-    pub fn barter_world() -> Card {
+    fn barter_world() -> CardDef {
         Self::base("Barter World".to_string(), Abilities {
             on_board: Some(Or(Box::new(AbilityFactory::give_authority(2)), Box::new(AbilityFactory::give_trade(2)))),
             on_faction: None,
@@ -257,7 +341,7 @@ impl CardFactory {
         }, Some(Trade), Some(4), 4, false)
     }
 
-    pub fn defense_center() -> Card {
+    fn defense_center() -> CardDef {
         Self::base("Defense Center".to_string(), Abilities {
             on_board: Some(Or(Box::new(AbilityFactory::give_authority(3)), Box::new(AbilityFactory::give_damages(2)))),
             on_faction: Some(AbilityFactory::give_damages(2)),
@@ -265,7 +349,7 @@ impl CardFactory {
         }, Some(Trade), Some(5), 5, true)
     }
 
-    pub fn central_office() -> Card {
+    fn central_office() -> CardDef {
         Self::base("Central Office".to_string(), Abilities {
             on_board: Some(And(Box::new(AbilityFactory::give_trade(2)), Box::new(AbilityFactory::next_ship_on_top()))),
             on_faction: Some(AbilityFactory::draw(1)),
@@ -273,8 +357,8 @@ impl CardFactory {
         }, Some(Trade), Some(7), 6, false)
     }
 
-    pub fn port_of_call() -> Card {
-        Self::base("Port Of Call".to_string(), Abilities {
+    fn port_of_call() -> CardDef {
+        Self::base("Port of Call".to_string(), Abilities {
             on_board: Some(AbilityFactory::give_trade(3)),
             on_faction: None,
             on_scrap: Some(And(Box::new(AbilityFactory::draw(1)), Box::new(AbilityFactory::destroy_target_base()))),
@@ -282,7 +366,7 @@ impl CardFactory {
     }
     // Blob
 
-    pub fn blob_fighter() -> Card {
+    fn blob_fighter() -> CardDef {
         Self::faction("Blob Fighter".to_string(), Abilities {
             on_board: Some(AbilityFactory::give_damages(3)),
             on_faction: Some(AbilityFactory::draw(1)),
@@ -290,7 +374,7 @@ impl CardFactory {
         }, Blob, Some(1))
     }
 
-    pub fn trade_pod() -> Card {
+    fn trade_pod() -> CardDef {
         Self::faction("Trade Pod".to_string(), Abilities {
             on_board: Some(AbilityFactory::give_trade(3)),
             on_faction: Some(AbilityFactory::give_damages(2)),
@@ -298,7 +382,7 @@ impl CardFactory {
         }, Blob, Some(2))
     }
 
-    pub fn battle_pod() -> Card {
+    fn battle_pod() -> CardDef {
         Self::faction("Battle Pod".to_string(), Abilities {
             on_board: Some(And(Box::new(AbilityFactory::give_damages(4)), Box::new(AbilityFactory::scrap_trade_row()))),
             on_faction: Some(AbilityFactory::give_damages(2)),
@@ -306,7 +390,7 @@ impl CardFactory {
         }, Blob, Some(2))
     }
 
-    pub fn ram() -> Card {
+    fn ram() -> CardDef {
         Self::faction("Ram".to_string(), Abilities {
             on_board: Some(AbilityFactory::give_damages(5)),
             on_faction: Some(AbilityFactory::give_damages(2)),
@@ -314,7 +398,7 @@ impl CardFactory {
         }, Blob, Some(3))
     }
 
-    pub fn blob_destroyer() -> Card {
+    fn blob_destroyer() -> CardDef {
         Self::faction("Blob Destroyer".to_string(), Abilities {
             on_board: Some(AbilityFactory::give_damages(6)),
             on_faction: Some(And(
@@ -325,7 +409,7 @@ impl CardFactory {
         }, Blob, Some(4))
     }
 
-    pub fn battle_blob() -> Card {
+    fn battle_blob() -> CardDef {
         Self::faction("Battle Blob".to_string(), Abilities {
             on_board: Some(AbilityFactory::give_damages(8)),
             on_faction: Some(AbilityFactory::draw(1)),
@@ -333,7 +417,7 @@ impl CardFactory {
         }, Blob, Some(6))
     }
 
-    pub fn blob_carrier() -> Card {
+    fn blob_carrier() -> CardDef {
         Self::faction("Blob Carrier".to_string(), Abilities {
             on_board: Some(AbilityFactory::give_damages(7)),
             on_faction: Some(AbilityFactory::free_ship_on_top()),
@@ -341,7 +425,7 @@ impl CardFactory {
         }, Blob, Some(6))
     }
 
-    pub fn mothership() -> Card {
+    fn mothership() -> CardDef {
         Self::faction("Mothership".to_string(), Abilities {
             on_board: Some(And(
                 Box::new(AbilityFactory::give_damages(6)),
@@ -352,7 +436,7 @@ impl CardFactory {
         }, Blob, Some(7))
     }
 
-    pub fn blob_wheel() -> Card {
+    fn blob_wheel() -> CardDef {
         Self::base("Blob Wheel".to_string(), Abilities {
             on_board: Some(AbilityFactory::give_damages(1)),
             on_faction: None,
@@ -360,7 +444,7 @@ impl CardFactory {
         }, Some(Blob), Some(3), 5, false)
     }
 
-    pub fn the_hive() -> Card {
+    fn the_hive() -> CardDef {
         Self::base("The Hive".to_string(), Abilities {
             on_board: Some(AbilityFactory::give_damages(3)),
             on_faction: Some(AbilityFactory::draw(1)),
@@ -368,7 +452,7 @@ impl CardFactory {
         }, Some(Blob), Some(5), 5, false)
     }
 
-    pub fn blob_world() -> Card {
+    fn blob_world() -> CardDef {
         Self::base("Blob World".to_string(), Abilities {
             on_board: Some(Or(
                 Box::new(AbilityFactory::give_damages(5)),
@@ -379,7 +463,7 @@ impl CardFactory {
         }, Some(Blob), Some(8), 7, false)
     }
     // Star Empire
-    pub fn imperial_fighter() -> Card {
+    fn imperial_fighter() -> CardDef {
         Self::faction("Imperial Fighter".to_string(), Abilities {
             on_board: Some(And(
                 Box::new(AbilityFactory::give_damages(2)),
@@ -390,7 +474,7 @@ impl CardFactory {
         }, Star, Some(1))
     }
 
-    pub fn imperial_frigate() -> Card {
+    fn imperial_frigate() -> CardDef {
         Self::faction("Imperial Frigate".to_string(), Abilities {
             on_board: Some(And(
                 Box::new(AbilityFactory::give_damages(4)),
@@ -401,7 +485,7 @@ impl CardFactory {
         }, Star, Some(3))
     }
 
-    pub fn survey_ship() -> Card {
+    fn survey_ship() -> CardDef {
         Self::faction("Survey Ship".to_string(), Abilities {
             on_board: Some(And(
                 Box::new(AbilityFactory::give_trade(1)),
@@ -412,7 +496,7 @@ impl CardFactory {
         }, Star, Some(3))
     }
 
-    pub fn corvette() -> Card {
+    fn corvette() -> CardDef {
         Self::faction("Corvette".to_string(), Abilities {
             on_board: Some(And(
                 Box::new(AbilityFactory::give_damages(1)),
@@ -423,7 +507,7 @@ impl CardFactory {
         }, Star, Some(2))
     }
 
-    pub fn battlecruiser() -> Card {
+    fn battlecruiser() -> CardDef {
         Self::faction("Battlecruiser".to_string(), Abilities {
             on_board: Some(And(
                 Box::new(AbilityFactory::give_damages(5)),
@@ -437,7 +521,7 @@ impl CardFactory {
         }, Star, Some(6))
     }
 
-    pub fn dreadnaught() -> Card {
+    fn dreadnaught() -> CardDef {
         Self::faction("Dreadnaught".to_string(), Abilities {
             on_board: Some(And(
                 Box::new(AbilityFactory::give_damages(7)),
@@ -448,7 +532,7 @@ impl CardFactory {
         }, Star, Some(7))
     }
 
-    pub fn space_station() -> Card {
+    fn space_station() -> CardDef {
         Self::base("Space Station".to_string(), Abilities {
             on_board: Some(AbilityFactory::give_damages(2)),
             on_faction: Some(AbilityFactory::give_damages(2)),
@@ -456,7 +540,7 @@ impl CardFactory {
         }, Some(Star), Some(4), 4, true)
     }
 
-    pub fn recycling_station() -> Card {
+    fn recycling_station() -> CardDef {
         Self::base("Recycling Station".to_string(), Abilities {
             on_board: Some(Or(
                 Box::new(AbilityFactory::give_trade(1)),
@@ -464,35 +548,35 @@ impl CardFactory {
             )),
             on_faction: None,
             on_scrap: None
-        }, Some(Trade), Some(4), 4, true)
+        }, Some(Star), Some(4), 4, true)
     }
 
-    pub fn war_world() -> Card {
+    fn war_world() -> CardDef {
         Self::base("War World".to_string(), Abilities {
             on_board: Some(AbilityFactory::give_damages(3)),
             on_faction: Some(AbilityFactory::give_damages(4)),
             on_scrap: None
-        }, Some(Trade), Some(5), 4, true)
+        }, Some(Star), Some(5), 4, true)
     }
 
-    pub fn royal_redoubt() -> Card {
+    fn royal_redoubt() -> CardDef {
         Self::base("Royal Redoubt".to_string(), Abilities {
             on_board: Some(AbilityFactory::give_damages(3)),
             on_faction: Some(AbilityFactory::target_discard()),
             on_scrap: None
-        }, Some(Trade), Some(5), 6, true)
+        }, Some(Star), Some(6), 6, true)
     }
 
-    pub fn fleet_hq() -> Card {
+    fn fleet_hq() -> CardDef {
         Self::base("Fleet HQ".to_string(), Abilities {
             on_board: Some(AbilityFactory::all_ships_get(1)),
             on_faction: None,
             on_scrap: None
-        }, Some(Trade), Some(5), 8, false)
+        }, Some(Star), Some(8), 8, false)
     }
 
     // Machine Cult
-    pub fn trade_bot() -> Card {
+    fn trade_bot() -> CardDef {
         Self::faction("Trade Bot".to_string(), Abilities {
             on_board: Some(And(
                 Box::new(AbilityFactory::give_trade(1)),
@@ -503,7 +587,7 @@ impl CardFactory {
         }, Machine, Some(1))
     }
 
-    pub fn missile_bot() -> Card {
+    fn missile_bot() -> CardDef {
         Self::faction("Missile Bot".to_string(), Abilities {
             on_board: Some(And(
                 Box::new(AbilityFactory::give_damages(2)),
@@ -514,7 +598,7 @@ impl CardFactory {
         }, Machine, Some(2))
     }
 
-    pub fn supply_bot() -> Card {
+    fn supply_bot() -> CardDef {
         Self::faction("Supply Bot".to_string(), Abilities {
             on_board: Some(And(
                 Box::new(AbilityFactory::give_trade(2)),
@@ -525,7 +609,7 @@ impl CardFactory {
         }, Machine, Some(3))
     }
 
-    pub fn battle_station() -> Card {
+    fn battle_station() -> CardDef {
         Self::base("Battle Station".to_string(), Abilities {
             on_board: None,
             on_faction: None,
@@ -533,8 +617,8 @@ impl CardFactory {
         }, Some(Machine), Some(3), 5, true)
     }
 
-    pub fn patrol_mech() -> Card {
-        Self::faction("Patrol Merch".to_string(), Abilities {
+    fn patrol_mech() -> CardDef {
+        Self::faction("Patrol Mech".to_string(), Abilities {
             on_board: Some(Or(
                 Box::new(AbilityFactory::give_trade(3)),
                 Box::new(AbilityFactory::give_damages(5))
@@ -544,7 +628,7 @@ impl CardFactory {
         }, Machine, Some(4))
     }
 
-    pub fn stealth_needle() -> Card {
+    fn stealth_needle() -> CardDef {
         Self::faction("Stealth Needle".to_string(), Abilities {
             on_board: Some(AbilityFactory::copy_ship()),
             on_faction: None,
@@ -552,8 +636,8 @@ impl CardFactory {
         }, Machine, Some(4))
     }
 
-    pub fn battle_mech() -> Card {
-        Self::faction("Battle Merch".to_string(), Abilities {
+    fn battle_mech() -> CardDef {
+        Self::faction("Battle Mech".to_string(), Abilities {
             on_board: Some(And(
                 Box::new(AbilityFactory::give_damages(4)),
                 Box::new(AbilityFactory::scrap_at_most(1))
@@ -563,8 +647,8 @@ impl CardFactory {
         }, Machine, Some(5))
     }
 
-    pub fn missile_mech() -> Card {
-        Self::faction("Missile Merch".to_string(), Abilities {
+    fn missile_mech() -> CardDef {
+        Self::faction("Missile Mech".to_string(), Abilities {
             on_board: Some(And(
                 Box::new(AbilityFactory::give_damages(6)),
                 Box::new(AbilityFactory::destroy_target_base())
@@ -574,7 +658,7 @@ impl CardFactory {
         }, Machine, Some(6))
     }
 
-    pub fn mech_world() -> Card {
+    fn mech_world() -> CardDef {
         Self::base("Mech World".to_string(), Abilities {
             on_board: Some(AbilityFactory::ally_to_all()),
             on_faction: None,
@@ -582,7 +666,7 @@ impl CardFactory {
         }, Some(Machine), Some(5), 6, true)
     }
 
-    pub fn brain_world() -> Card {
+    fn brain_world() -> CardDef {
         Self::base("Brain World".to_string(), Abilities {
             on_board: Some(AbilityFactory::scrap_n_draw_n(2)),
             on_faction: None,
@@ -590,7 +674,7 @@ impl CardFactory {
         }, Some(Machine), Some(8), 6, true)
     }
 
-    pub fn machine_base() -> Card {
+    fn machine_base() -> CardDef {
         Self::base("Machine Base".to_string(), Abilities {
             on_board: Some(AbilityFactory::draw_then_scrap()),
             on_faction: None,
@@ -598,7 +682,7 @@ impl CardFactory {
         }, Some(Machine), Some(7), 6, true)
     }
 
-    pub fn junkyard() -> Card {
+    fn junkyard() -> CardDef {
         Self::base("Junkyard".to_string(), Abilities {
             on_board: Some(AbilityFactory::scrap_at_most(1)),
             on_faction: None,
@@ -606,11 +690,88 @@ impl CardFactory {
         }, Some(Machine), Some(6), 5, true)
     }
 
+    /// What `card` is and does
+    pub fn definition(card: Card) -> CardDef {
+        match card {
+            Card::Scout => Self::scout(),
+            Card::Viper => Self::viper(),
+            Card::Explorer => Self::explorer(),
+            Card::FederationShuttle => Self::federation_shuttle(),
+            Card::Cutter => Self::cutter(),
+            Card::EmbassyYacht => Self::embassy_yacht(),
+            Card::Freighter => Self::freighter(),
+            Card::CommandShip => Self::command_ship(),
+            Card::TradeEscort => Self::trade_escort(),
+            Card::Flagship => Self::flagship(),
+            Card::TradingPost => Self::trading_post(),
+            Card::BarterWorld => Self::barter_world(),
+            Card::DefenseCenter => Self::defense_center(),
+            Card::CentralOffice => Self::central_office(),
+            Card::PortOfCall => Self::port_of_call(),
+            Card::BlobFighter => Self::blob_fighter(),
+            Card::TradePod => Self::trade_pod(),
+            Card::BattlePod => Self::battle_pod(),
+            Card::Ram => Self::ram(),
+            Card::BlobDestroyer => Self::blob_destroyer(),
+            Card::BattleBlob => Self::battle_blob(),
+            Card::BlobCarrier => Self::blob_carrier(),
+            Card::Mothership => Self::mothership(),
+            Card::BlobWheel => Self::blob_wheel(),
+            Card::TheHive => Self::the_hive(),
+            Card::BlobWorld => Self::blob_world(),
+            Card::ImperialFighter => Self::imperial_fighter(),
+            Card::ImperialFrigate => Self::imperial_frigate(),
+            Card::SurveyShip => Self::survey_ship(),
+            Card::Corvette => Self::corvette(),
+            Card::Battlecruiser => Self::battlecruiser(),
+            Card::Dreadnaught => Self::dreadnaught(),
+            Card::SpaceStation => Self::space_station(),
+            Card::RecyclingStation => Self::recycling_station(),
+            Card::WarWorld => Self::war_world(),
+            Card::RoyalRedoubt => Self::royal_redoubt(),
+            Card::FleetHq => Self::fleet_hq(),
+            Card::TradeBot => Self::trade_bot(),
+            Card::MissileBot => Self::missile_bot(),
+            Card::SupplyBot => Self::supply_bot(),
+            Card::BattleStation => Self::battle_station(),
+            Card::PatrolMech => Self::patrol_mech(),
+            Card::StealthNeedle => Self::stealth_needle(),
+            Card::BattleMech => Self::battle_mech(),
+            Card::MissileMech => Self::missile_mech(),
+            Card::MechWorld => Self::mech_world(),
+            Card::BrainWorld => Self::brain_world(),
+            Card::MachineBase => Self::machine_base(),
+            Card::Junkyard => Self::junkyard(),
+        }
+    }
+
+    /// One card of each kind, in a fixed order used to index cards in numeric encodings
+    pub fn catalog() -> Vec<Card> {
+        Card::ALL.to_vec()
+    }
+
+    /// The card of `catalog` with this name
+    pub fn by_name(name: &str) -> Option<Card> {
+        Card::ALL.into_iter().find(|c| c.get_name() == name)
+    }
+
     pub fn n_of(n: usize, c: Card) -> Vec<Card> {
-        let mut sink = vec![];
-        for _ in 0..n {
-            sink.push(c.clone());
+        vec![c; n]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_card_ids() {
+        for (i, card) in Card::ALL.into_iter().enumerate() {
+            assert_eq!(card.index(), i);
+            assert_eq!(CardFactory::by_name(&card.get_name()), Some(card));
         }
-        sink
+        assert_eq!(Card::StealthNeedle.get_faction(), Some(Machine));
+        assert!(Card::TradingPost.is_outpost() && !Card::BlobWheel.is_outpost());
+        assert_eq!(Card::filter_bases(vec![Card::Scout, Card::TheHive, Card::Ram]), vec![Card::TheHive]);
     }
 }
@@ -0,0 +1,110 @@
+use std::fmt::{Display, Formatter};
+use std::str::FromStr;
+
+/// What happens to a player whose time runs out
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize), serde(rename_all = "snake_case"))]
+pub enum TimeoutAction {
+    /// The turn ends as if the player had ended it
+    EndTurn,
+    /// The player loses the game
+    Forfeit,
+}
+
+/// Chess clock settings: a time budget per player, refilled by `increment_ms` at the end
+/// of each of their turns
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct TimeControl {
+    pub total_ms: u64,
+    pub increment_ms: u64,
+    pub on_timeout: TimeoutAction,
+}
+
+/// The time left to each player
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Clock {
+    pub control: TimeControl,
+    pub remaining_ms: [u64; 2],
+    /// The player who lost on time
+    pub flagged: Option<i32>,
+}
+
+impl Clock {
+    pub fn new(control: TimeControl) -> Clock {
+        Clock {
+            control,
+            remaining_ms: [control.total_ms; 2],
+            flagged: None,
+        }
+    }
+
+    /// Takes `elapsed_ms` from the time of `player`, never below zero
+    pub fn spend(&mut self, player: i32, elapsed_ms: u64) {
+        let remaining = &mut self.remaining_ms[player as usize % 2];
+        *remaining = remaining.saturating_sub(elapsed_ms);
+    }
+
+    pub fn is_out(&self, player: i32) -> bool {
+        self.remaining_ms[player as usize % 2] == 0
+    }
+
+    /// Gives the increment to `player`, who just ended a turn
+    pub fn end_turn(&mut self, player: i32) {
+        self.remaining_ms[player as usize % 2] += self.control.increment_ms;
+    }
+}
+
+/// Text form in seconds used by replays, e.g. `300+5 end` or `600+0 forfeit`
+impl Display for TimeControl {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let on_timeout = match self.on_timeout {
+            TimeoutAction::EndTurn => "end",
+            TimeoutAction::Forfeit => "forfeit",
+        };
+        write!(f, "{}+{} {on_timeout}", self.total_ms as f64 / 1000.0, self.increment_ms as f64 / 1000.0)
+    }
+}
+
+impl FromStr for TimeControl {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let ms = |w: &str| w.trim().parse::<f64>()
+            .ok()
+            .filter(|s| *s >= 0.0)
+            .map(|s| (s * 1000.0).round() as u64)
+            .ok_or(format!("Not a number of seconds: {w}"));
+        let (times, on_timeout) = s.trim().split_once(' ').ok_or(format!("Missing timeout action in: {s}"))?;
+        let (total, increment) = times.split_once('+').ok_or(format!("Missing '+' in: {s}"))?;
+        Ok(TimeControl {
+            total_ms: ms(total)?,
+            increment_ms: ms(increment)?,
+            on_timeout: match on_timeout.trim() {
+                "end" => TimeoutAction::EndTurn,
+                "forfeit" => TimeoutAction::Forfeit,
+                a => return Err(format!("Unknown timeout action: {a}")),
+            },
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_time_control_text() {
+        let control: TimeControl = "300+2.5 forfeit".parse().unwrap();
+        assert_eq!(control, TimeControl { total_ms: 300_000, increment_ms: 2_500, on_timeout: TimeoutAction::Forfeit });
+        assert_eq!(control.to_string().parse::<TimeControl>(), Ok(control));
+        assert!("300 end".parse::<TimeControl>().is_err());
+
+        let mut clock = Clock::new(control);
+        clock.spend(1, 400_000);
+        assert!(clock.is_out(1) && !clock.is_out(0));
+        clock.end_turn(1);
+        assert_eq!(clock.remaining_ms, [300_000, 2_500]);
+    }
+}
@@ -0,0 +1,216 @@
+use rand::{thread_rng, Rng};
+use crate::gamelogic::{Action, GameLogic, Target};
+use crate::star_realms::GameState;
+
+pub const MAX_HAND: usize = 20;
+pub const TRADE_ROW: usize = 5;
+pub const MAX_PLAYED: usize = 20;
+pub const MAX_ABILITIES: usize = 10;
+pub const MAX_BASES: usize = 10;
+pub const MAX_DISCARD: usize = 40;
+
+/// Size of each group of actions, in the order used by `ActionSpace`
+const SEGMENTS: [usize; 17] = [
+    MAX_HAND,     // Play
+    TRADE_ROW,    // Buy
+    1,            // BuyExplorer
+    MAX_PLAYED,   // Ally
+    MAX_PLAYED,   // Scrap
+    MAX_ABILITIES,// Activate
+    1,            // AttackPlayer
+    MAX_BASES,    // AttackBase
+    2,            // Choose
+    MAX_BASES,    // Select EnemyBase
+    TRADE_ROW,    // Select TradeRow
+    MAX_PLAYED,   // Select Played
+    MAX_DISCARD,  // Select Discarded
+    MAX_HAND,     // Select Playable
+    1,            // Confirm
+    1,            // Decline
+    1,            // EndTurn
+];
+
+/// Fixed size numbering of the actions. Actions on cards beyond the zone limits above
+/// have no index and are never offered to agents.
+pub struct ActionSpace;
+
+impl ActionSpace {
+    pub fn size() -> usize {
+        SEGMENTS.iter().sum()
+    }
+
+    fn offset(segment: usize) -> usize {
+        SEGMENTS[..segment].iter().sum()
+    }
+
+    pub fn index(action: &Action) -> Option<usize> {
+        let (segment, i) = match *action {
+            Action::Play(i) => (0, i),
+            Action::Buy(i) => (1, i),
+            Action::BuyExplorer => (2, 0),
+            Action::Ally(i) => (3, i),
+            Action::Scrap(i) => (4, i),
+            Action::Activate(i) => (5, i),
+            Action::AttackPlayer => (6, 0),
+            Action::AttackBase(i) => (7, i),
+            Action::Choose(i) => (8, i),
+            Action::Select(Target::EnemyBase(i)) => (9, i),
+            Action::Select(Target::TradeRow(i)) => (10, i),
+            Action::Select(Target::Played(i)) => (11, i),
+            Action::Select(Target::Discarded(i)) => (12, i),
+            Action::Select(Target::Playable(i)) => (13, i),
+            Action::Confirm => (14, 0),
+            Action::Decline => (15, 0),
+            Action::EndTurn => (16, 0),
+            Action::Timeout => return None,
+        };
+        if i < SEGMENTS[segment] {
+            Some(Self::offset(segment) + i)
+        } else {
+            None
+        }
+    }
+
+    pub fn action(index: usize) -> Option<Action> {
+        let mut i = index;
+        for (segment, size) in SEGMENTS.iter().enumerate() {
+            if i >= *size {
+                i -= size;
+                continue;
+            }
+            return Some(match segment {
+                0 => Action::Play(i),
+                1 => Action::Buy(i),
+                2 => Action::BuyExplorer,
+                3 => Action::Ally(i),
+                4 => Action::Scrap(i),
+                5 => Action::Activate(i),
+                6 => Action::AttackPlayer,
+                7 => Action::AttackBase(i),
+                8 => Action::Choose(i),
+                9 => Action::Select(Target::EnemyBase(i)),
+                10 => Action::Select(Target::TradeRow(i)),
+                11 => Action::Select(Target::Played(i)),
+                12 => Action::Select(Target::Discarded(i)),
+                13 => Action::Select(Target::Playable(i)),
+                14 => Action::Confirm,
+                15 => Action::Decline,
+                _ => Action::EndTurn,
+            });
+        }
+        None
+    }
+
+    /// One flag per action index, set for the legal actions of `gs`
+    pub fn mask(gs: &GameState) -> Vec<bool> {
+        let mut mask = vec![false; Self::size()];
+        for action in GameLogic::legal_actions(gs) {
+            if let Some(i) = Self::index(&action) {
+                mask[i] = true;
+            }
+        }
+        mask
+    }
+}
+
+/// What the player to act gets to see
+#[derive(Debug, Clone)]
+pub struct Observation {
+    pub player: i32,
+    pub features: Vec<f32>,
+    pub action_mask: Vec<bool>,
+}
+
+#[derive(Debug, Clone)]
+pub struct Info {
+    pub turn: i32,
+    pub winner: Option<i32>,
+    pub action: Action,
+}
+
+/// Gym-style environment. Rewards are given to the player who took the action:
+/// 1 for a win, -1 for a loss and 0 otherwise.
+pub struct StarRealmsEnv {
+    state: GameState,
+}
+
+impl Default for StarRealmsEnv {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl StarRealmsEnv {
+    pub fn new() -> StarRealmsEnv {
+        StarRealmsEnv {
+            state: GameState::with_seed(thread_rng().gen()),
+        }
+    }
+
+    pub fn observation_size() -> usize {
+        GameState::encoded_len()
+    }
+
+    pub fn state(&self) -> &GameState {
+        &self.state
+    }
+
+    pub fn observe(&self) -> Observation {
+        let player = self.state.turn % 2;
+        Observation {
+            player,
+            features: self.state.encode(player).expect("the player to move is 0 or 1"),
+            action_mask: ActionSpace::mask(&self.state),
+        }
+    }
+
+    pub fn reset(&mut self, seed: u64) -> Observation {
+        self.state = GameState::with_seed(seed);
+        self.observe()
+    }
+
+    pub fn step(&mut self, action_index: usize) -> Result<(Observation, f32, bool, Info), String> {
+        let action = ActionSpace::action(action_index).ok_or(format!("No action at index {action_index}"))?;
+        let player = self.state.turn % 2;
+        self.state = GameLogic::apply(&self.state, &action)?;
+        let winner = self.state.winner();
+        let reward = match winner {
+            Some(w) if w == player => 1.,
+            Some(_) => -1.,
+            None => 0.,
+        };
+        let info = Info {
+            turn: self.state.turn,
+            winner,
+            action,
+        };
+        Ok((self.observe(), reward, winner.is_some(), info))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_action_space_roundtrip() {
+        for i in 0..ActionSpace::size() {
+            let action = ActionSpace::action(i).unwrap();
+            assert_eq!(ActionSpace::index(&action), Some(i));
+        }
+        assert!(ActionSpace::action(ActionSpace::size()).is_none());
+    }
+
+    #[test]
+    fn test_reset_is_seeded() {
+        let mut env = StarRealmsEnv::new();
+        let a = env.reset(42);
+        let b = env.reset(42);
+        assert_eq!(a.features, b.features);
+        assert_eq!(a.features.len(), StarRealmsEnv::observation_size());
+        let (obs, reward, done, _) = env.step(ActionSpace::index(&Action::Play(0)).unwrap()).unwrap();
+        assert_eq!(reward, 0.);
+        assert!(!done);
+        assert!(obs.action_mask[ActionSpace::index(&Action::EndTurn).unwrap()]);
+    }
+}
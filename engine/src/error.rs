@@ -0,0 +1,65 @@
+use std::fmt::{Display, Formatter};
+use crate::star_realms::Zone;
+
+/// Why the rules refuse an action
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RuleError {
+    GameOver,
+    /// Something else has to be done first, which the text tells
+    WrongPhase(&'static str),
+    /// Players are 0 and 1
+    InvalidPlayer(i32),
+    /// No card at this index of the named zone
+    CardNotInZone(&'static str),
+    NotEnoughTrade,
+    NotEnoughDamage,
+    OutpostBlocksAttack,
+    /// The named card has no ability of this kind
+    NoSuchAbility(String),
+    AbilityAlreadyUsed,
+    /// The named card has no ally in play for its ally ability
+    NoAlly(String),
+    /// The pending decision cannot take this card, for the reason given
+    InvalidTarget(&'static str),
+    /// The pending decision takes at most this many cards
+    TooManyTargets(usize),
+    /// The pending decision takes at least this many cards while there are some left
+    TooFewTargets(usize),
+    /// The named card cannot move between these zones
+    InvalidTransfer(String, Zone, Zone),
+    NoClock,
+    /// Only a player without time left can time out
+    TimeLeft,
+}
+
+impl Display for RuleError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            RuleError::GameOver => write!(f, "The game is over"),
+            RuleError::WrongPhase(what) => write!(f, "{what}"),
+            RuleError::InvalidPlayer(id) => write!(f, "There is no player {id}"),
+            RuleError::CardNotInZone(zone) => write!(f, "No such card in the {zone}"),
+            RuleError::NotEnoughTrade => write!(f, "Not enough trade"),
+            RuleError::NotEnoughDamage => write!(f, "Not enough damages"),
+            RuleError::OutpostBlocksAttack => write!(f, "An outpost protects the opponent"),
+            RuleError::NoSuchAbility(card) => write!(f, "{card} has no such ability"),
+            RuleError::AbilityAlreadyUsed => write!(f, "This ability was already used"),
+            RuleError::NoAlly(card) => write!(f, "{card} has no ally in play"),
+            RuleError::InvalidTarget(why) => write!(f, "{why}"),
+            RuleError::TooManyTargets(max) => write!(f, "At most {max} cards can be selected"),
+            RuleError::TooFewTargets(min) => write!(f, "At least {min} cards must be selected"),
+            RuleError::InvalidTransfer(card, from, to) => write!(f, "{card} cannot go from the {from} to the {to}"),
+            RuleError::NoClock => write!(f, "The game has no clock"),
+            RuleError::TimeLeft => write!(f, "There is time left"),
+        }
+    }
+}
+
+impl std::error::Error for RuleError {}
+
+/// For the frontends and tools, which report errors as text
+impl From<RuleError> for String {
+    fn from(e: RuleError) -> String {
+        e.to_string()
+    }
+}
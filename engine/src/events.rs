@@ -0,0 +1,58 @@
+use std::fmt::{Display, Formatter};
+use crate::cards::Card;
+
+/// Something that happened during a transition, see `GameLogic::step`. Players are 0 and 1,
+/// cards are serialized by name.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize), serde(tag = "type", rename_all = "snake_case"))]
+pub enum Event {
+    CardPlayed {
+        player: i32,
+        #[cfg_attr(feature = "serde", serde(with = "crate::protocol::card_name"))]
+        card: Card,
+    },
+    CardAcquired {
+        player: i32,
+        #[cfg_attr(feature = "serde", serde(with = "crate::protocol::card_name"))]
+        card: Card,
+    },
+    /// `player` is the one whose turn it is, who scrapped the card
+    CardScrapped {
+        player: i32,
+        #[cfg_attr(feature = "serde", serde(with = "crate::protocol::card_name"))]
+        card: Card,
+    },
+    /// A base of `player` was destroyed
+    BaseDestroyed {
+        player: i32,
+        #[cfg_attr(feature = "serde", serde(with = "crate::protocol::card_name"))]
+        card: Card,
+    },
+    /// `player` lost `amount` authority to an attack
+    DamageDealt { player: i32, amount: i32 },
+    AuthorityGained { player: i32, amount: i32 },
+    CardsDrawn { player: i32, count: usize },
+    /// The discard pile of `player` became their deck
+    Shuffled { player: i32 },
+    TurnEnded { player: i32 },
+    GameWon { player: i32 },
+}
+
+/// A line of a game log
+impl Display for Event {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Event::CardPlayed { player, card } => write!(f, "Player {} plays {}", player + 1, card.get_name()),
+            Event::CardAcquired { player, card } => write!(f, "Player {} acquires {}", player + 1, card.get_name()),
+            Event::CardScrapped { player, card } => write!(f, "Player {} scraps {}", player + 1, card.get_name()),
+            Event::BaseDestroyed { player, card } => write!(f, "{} of player {} is destroyed", card.get_name(), player + 1),
+            Event::DamageDealt { player, amount } => write!(f, "Player {} takes {amount} damages", player + 1),
+            Event::AuthorityGained { player, amount } => write!(f, "Player {} gains {amount} authority", player + 1),
+            Event::CardsDrawn { player, count: 1 } => write!(f, "Player {} draws a card", player + 1),
+            Event::CardsDrawn { player, count } => write!(f, "Player {} draws {count} cards", player + 1),
+            Event::Shuffled { player } => write!(f, "Player {} shuffles their discard pile", player + 1),
+            Event::TurnEnded { player } => write!(f, "Player {} ends the turn", player + 1),
+            Event::GameWon { player } => write!(f, "Player {} wins", player + 1),
+        }
+    }
+}
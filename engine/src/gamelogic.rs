@@ -0,0 +1,816 @@
+use std::fmt::{Display, Formatter};
+use std::str::FromStr;
+use std::sync::LazyLock;
+use crate::abilities::{Abilities, Ability, AbilityFactory, AfterCapacity, AtomicAbility, AtomicAbilityFn, ChoicesSources};
+use crate::cards::{Card, Faction};
+use crate::clock::TimeoutAction;
+use crate::error::RuleError;
+use crate::events::Event;
+use crate::star_realms::{GameState, Player, Zone};
+
+/// A card picked while resolving a decision, given by its zone and its index in that zone
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Target {
+    /// Index among the opponent's played bases
+    EnemyBase(usize),
+    TradeRow(usize),
+    Played(usize),
+    Discarded(usize),
+    Playable(usize),
+}
+
+impl Target {
+    /// Name of the zone, for messages
+    pub fn zone(&self) -> &'static str {
+        match self {
+            Target::EnemyBase(_) => "opponent's bases",
+            Target::TradeRow(_) => "trade row",
+            Target::Played(_) => "played cards",
+            Target::Discarded(_) => "discard pile",
+            Target::Playable(_) => "hand",
+        }
+    }
+
+    pub fn source(&self) -> ChoicesSources {
+        match self {
+            Target::EnemyBase(_) => ChoicesSources::EnemyBase,
+            Target::TradeRow(_) => ChoicesSources::TradeRow,
+            Target::Played(_) => ChoicesSources::Played,
+            Target::Discarded(_) => ChoicesSources::Discarded,
+            Target::Playable(_) => ChoicesSources::Playable,
+        }
+    }
+}
+
+/// Everything the current player can do. Indices refer to the zones of the current state.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize), serde(into = "String", try_from = "String"))]
+pub enum Action {
+    /// Play a card from the hand
+    Play(usize),
+    /// Acquire a card of the trade row
+    Buy(usize),
+    BuyExplorer,
+    /// Use the ally ability of a played card
+    Ally(usize),
+    /// Scrap a played card to use its scrap ability
+    Scrap(usize),
+    /// Use one of the delayed abilities of the hand
+    Activate(usize),
+    AttackPlayer,
+    /// Attack one of the opponent's bases, by index among its bases
+    AttackBase(usize),
+    /// Pick a branch of a pending `Ability::Or`, 0 for the left one
+    Choose(usize),
+    /// Pick a card for the pending decision
+    Select(Target),
+    /// Resolve the pending decision with the selected cards
+    Confirm,
+    /// Skip the pending decision, both branches of a choice included
+    Decline,
+    EndTurn,
+    /// Applies the timeout action of the clock, once the current player has no time left.
+    /// Never a legal action: whoever runs the clock plays it.
+    Timeout,
+}
+
+/// Text form used by replays, e.g. `play 0`, `attack base 1` or `select discard 3`
+impl Display for Action {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Action::Play(i) => write!(f, "play {i}"),
+            Action::Buy(i) => write!(f, "buy {i}"),
+            Action::BuyExplorer => write!(f, "buy explorer"),
+            Action::Ally(i) => write!(f, "ally {i}"),
+            Action::Scrap(i) => write!(f, "scrap {i}"),
+            Action::Activate(i) => write!(f, "activate {i}"),
+            Action::AttackPlayer => write!(f, "attack player"),
+            Action::AttackBase(i) => write!(f, "attack base {i}"),
+            Action::Choose(i) => write!(f, "choose {i}"),
+            Action::Select(t) => match t {
+                Target::EnemyBase(i) => write!(f, "select base {i}"),
+                Target::TradeRow(i) => write!(f, "select row {i}"),
+                Target::Played(i) => write!(f, "select played {i}"),
+                Target::Discarded(i) => write!(f, "select discard {i}"),
+                Target::Playable(i) => write!(f, "select hand {i}"),
+            },
+            Action::Confirm => write!(f, "confirm"),
+            Action::Decline => write!(f, "decline"),
+            Action::EndTurn => write!(f, "end"),
+            Action::Timeout => write!(f, "timeout"),
+        }
+    }
+}
+
+impl From<Action> for String {
+    fn from(action: Action) -> String {
+        action.to_string()
+    }
+}
+
+impl TryFrom<String> for Action {
+    type Error = String;
+
+    fn try_from(s: String) -> Result<Self, Self::Error> {
+        s.parse()
+    }
+}
+
+impl FromStr for Action {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let words: Vec<&str> = s.split_whitespace().collect();
+        let index = |w: &str| w.parse::<usize>().map_err(|_| format!("Not an index: {w}"));
+        Ok(match words[..] {
+            ["play", i] => Action::Play(index(i)?),
+            ["buy", "explorer"] => Action::BuyExplorer,
+            ["buy", i] => Action::Buy(index(i)?),
+            ["ally", i] => Action::Ally(index(i)?),
+            ["scrap", i] => Action::Scrap(index(i)?),
+            ["activate", i] => Action::Activate(index(i)?),
+            ["attack", "player"] => Action::AttackPlayer,
+            ["attack", "base", i] => Action::AttackBase(index(i)?),
+            ["choose", i] => Action::Choose(index(i)?),
+            ["select", zone, i] => Action::Select(match zone {
+                "base" => Target::EnemyBase(index(i)?),
+                "row" => Target::TradeRow(index(i)?),
+                "played" => Target::Played(index(i)?),
+                "discard" => Target::Discarded(index(i)?),
+                "hand" => Target::Playable(index(i)?),
+                _ => return Err(format!("Unknown zone: {zone}")),
+            }),
+            ["confirm"] => Action::Confirm,
+            ["decline"] => Action::Decline,
+            ["end"] => Action::EndTurn,
+            ["timeout"] => Action::Timeout,
+            _ => return Err(format!("Unknown action: {s}")),
+        })
+    }
+}
+
+/// An ability waiting for the current player to make a choice. Abilities are borrowed from the
+/// card definitions, so that states hold no closures of their own.
+#[derive(Debug, Clone)]
+pub struct Decision {
+    pub ability: &'static Ability,
+    pub selected: Vec<Target>,
+}
+
+impl Decision {
+    pub fn new(ability: &'static Ability) -> Decision {
+        Decision {
+            ability,
+            selected: vec![],
+        }
+    }
+}
+
+/// Queued by the abilities that scrap a card from the hand once used
+static SCRAP_FROM_HAND: LazyLock<Ability> = LazyLock::new(AbilityFactory::scrap_from_hand);
+/// Queued at the start of a turn for each card the opponent made the player discard
+static DISCARD: LazyLock<Ability> = LazyLock::new(AbilityFactory::discard);
+
+pub struct GameLogic {}
+
+impl GameLogic {
+    fn current_id(gs: &GameState) -> i32 {
+        gs.turn % 2
+    }
+
+    fn opponent_id(gs: &GameState) -> i32 {
+        (gs.turn + 1) % 2
+    }
+
+    fn is_ally_used(player: &Player, i: usize) -> bool {
+        player.hand.ally_used.get(i).copied().unwrap_or(false)
+    }
+
+    /// Factions of the played card `i` of `player`: a Stealth Needle also has the faction of the ship it copied
+    pub fn played_factions(player: &Player, i: usize) -> Vec<Faction> {
+        let card = match player.hand.played.get(i) {
+            Some(c) => c,
+            None => return vec![],
+        };
+        let mut factions: Vec<Faction> = card.get_faction().into_iter().collect();
+        if *card == Card::StealthNeedle {
+            factions.extend(player.hand.copied.and_then(|c| c.get_faction()));
+        }
+        factions
+    }
+
+    /// Abilities of the played card `i` of `player`: a Stealth Needle has those of the ship it copied
+    pub fn played_abilities(player: &Player, i: usize) -> Option<&'static Abilities> {
+        let card = player.hand.played.get(i)?;
+        match player.hand.copied {
+            Some(copied) if *card == Card::StealthNeedle => Some(copied.get_abilities()),
+            _ => Some(card.get_abilities()),
+        }
+    }
+
+    /// Tells if the played card `i` of `player` has an ally in play
+    pub fn has_ally(player: &Player, i: usize) -> bool {
+        let factions = Self::played_factions(player, i);
+        !factions.is_empty() && (0..player.hand.played.len()).any(|j| {
+            j != i && (Self::played_factions(player, j).iter().any(|f| factions.contains(f))
+                || player.hand.played[j].get_abilities().on_board.as_ref().is_some_and(|a| a.grants(&AfterCapacity::AllyToAll)))
+        })
+    }
+
+    /// Card designated by a target, seen from the current player
+    pub fn target_card(gs: &GameState, target: &Target) -> Option<Card> {
+        let me = gs.current_player();
+        let opponent = gs.opponent_player();
+        match *target {
+            Target::EnemyBase(i) => opponent.hand.get_played_bases().get(i).map(|c| **c),
+            Target::TradeRow(i) => gs.trade_row.get(i).cloned(),
+            Target::Played(i) => me.hand.played.get(i).cloned(),
+            Target::Discarded(i) => me.discard.get(i).cloned(),
+            Target::Playable(i) => me.hand.playable.get(i).cloned(),
+        }
+    }
+
+    fn targets_of(gs: &GameState, source: &ChoicesSources) -> Vec<Target> {
+        let me = gs.current_player();
+        let opponent = gs.opponent_player();
+        match source {
+            ChoicesSources::EnemyBase => (0..opponent.hand.get_played_bases().len()).map(Target::EnemyBase).collect(),
+            ChoicesSources::TradeRow => (0..gs.trade_row.len()).map(Target::TradeRow).collect(),
+            ChoicesSources::Played => (0..me.hand.played.len()).map(Target::Played).collect(),
+            ChoicesSources::Discarded => (0..me.discard.len()).map(Target::Discarded).collect(),
+            ChoicesSources::Playable => (0..me.hand.playable.len()).map(Target::Playable).collect(),
+            ChoicesSources::And(a, b) => {
+                let mut targets = Self::targets_of(gs, a);
+                targets.append(&mut Self::targets_of(gs, b));
+                targets
+            }
+        }
+    }
+
+    fn candidates(gs: &GameState) -> Vec<Action> {
+        let me = gs.current_player();
+        let opponent = gs.opponent_player();
+        if let Some(decision) = gs.pending.first() {
+            return match &decision.ability {
+                Ability::Atomic(a) => {
+                    let mut actions: Vec<Action> = a.choices_sources()
+                        .map_or(vec![], |s| Self::targets_of(gs, s))
+                        .into_iter()
+                        .map(Action::Select)
+                        .collect();
+                    actions.push(Action::Confirm);
+                    actions.push(Action::Decline);
+                    actions
+                }
+                _ => vec![Action::Choose(0), Action::Choose(1), Action::Decline],
+            };
+        }
+        let mut actions = vec![];
+        actions.extend((0..me.hand.playable.len()).map(Action::Play));
+        actions.extend((0..gs.trade_row.len()).map(Action::Buy));
+        actions.push(Action::BuyExplorer);
+        actions.extend((0..me.hand.played.len()).map(Action::Ally));
+        actions.extend((0..me.hand.played.len()).map(Action::Scrap));
+        actions.extend((0..me.hand.abilities.len()).map(Action::Activate));
+        actions.push(Action::AttackPlayer);
+        actions.extend((0..opponent.hand.get_played_bases().len()).map(Action::AttackBase));
+        actions.push(Action::EndTurn);
+        actions
+    }
+
+    /// Every action `check` accepts in this state
+    pub fn legal_actions(gs: &GameState) -> Vec<Action> {
+        Self::candidates(gs).into_iter().filter(|a| Self::check(gs, a).is_ok()).collect()
+    }
+
+    /// Whether `decision` has a card left to select
+    fn can_select(gs: &GameState, decision: &Decision, ability: &AtomicAbility) -> bool {
+        ability.choices_sources().is_some_and(|s| {
+            Self::targets_of(gs, s).into_iter().any(|t| Self::check_decision(gs, decision, &Action::Select(t)).is_ok())
+        })
+    }
+
+    fn check_decision(gs: &GameState, decision: &Decision, action: &Action) -> Result<(), RuleError> {
+        match (&decision.ability, action) {
+            (Ability::Or(_, _), Action::Choose(i)) if *i < 2 => Ok(()),
+            (Ability::Or(_, _), Action::Decline) => Ok(()),
+            (Ability::Or(_, _), _) => Err(RuleError::WrongPhase("Choose one of the two abilities or decline")),
+            (Ability::Atomic(a), Action::Select(t)) => {
+                if !a.choices_sources().is_some_and(|s| s.contains(&t.source())) {
+                    return Err(RuleError::InvalidTarget("This ability cannot target this zone"));
+                }
+                let card = Self::target_card(gs, t).ok_or(RuleError::CardNotInZone(t.zone()))?;
+                if let Target::Played(_) = t {
+                    if card.is_base() {
+                        return Err(RuleError::InvalidTarget("Only ships can be targeted"));
+                    }
+                    if card == Card::StealthNeedle {
+                        return Err(RuleError::InvalidTarget("Only another ship can be copied"));
+                    }
+                }
+                if decision.selected.contains(t) {
+                    return Err(RuleError::InvalidTarget("Card already selected"));
+                }
+                if decision.selected.len() >= a.max_choices() {
+                    return Err(RuleError::TooManyTargets(a.max_choices()));
+                }
+                Ok(())
+            }
+            (Ability::Atomic(a), Action::Confirm) => match a.ability() {
+                AtomicAbilityFn::Cards(_) | AtomicAbilityFn::CardsFromHandOrDiscard(_) => {
+                    if decision.selected.len() < a.min_choices() && Self::can_select(gs, decision, a) {
+                        return Err(RuleError::TooFewTargets(a.min_choices()));
+                    }
+                    Ok(())
+                }
+                _ => Err(RuleError::WrongPhase("Select a card or decline")),
+            },
+            (Ability::Atomic(a), Action::Decline) => {
+                if a.min_choices() > 0 && Self::can_select(gs, decision, a) {
+                    return Err(RuleError::TooFewTargets(a.min_choices()));
+                }
+                Ok(())
+            }
+            _ => Err(RuleError::WrongPhase("A decision is pending")),
+        }
+    }
+
+    /// Checks an action against the rules without applying it
+    pub fn check(gs: &GameState, action: &Action) -> Result<(), RuleError> {
+        if gs.is_over() {
+            return Err(RuleError::GameOver);
+        }
+        if *action == Action::Timeout {
+            return match gs.clock {
+                Some(clock) if clock.is_out(Self::current_id(gs)) => Ok(()),
+                Some(_) => Err(RuleError::TimeLeft),
+                None => Err(RuleError::NoClock),
+            };
+        }
+        if let Some(decision) = gs.pending.first() {
+            return Self::check_decision(gs, decision, action);
+        }
+        let me = gs.current_player();
+        let opponent = gs.opponent_player();
+        let opponent_bases = opponent.hand.get_played_bases();
+        let has_outpost = opponent_bases.iter().any(|b| b.is_outpost());
+        match *action {
+            Action::Play(i) => {
+                if i >= me.hand.playable.len() {
+                    return Err(RuleError::CardNotInZone("hand"));
+                }
+            }
+            Action::Buy(i) => {
+                let card = gs.trade_row.get(i).ok_or(RuleError::CardNotInZone("trade row"))?;
+                let free = me.hand.next_n_ships_free > 0 && !card.is_base();
+                if !free && card.get_cost().unwrap_or(0) > me.hand.trade {
+                    return Err(RuleError::NotEnoughTrade);
+                }
+            }
+            Action::BuyExplorer => {
+                let card = gs.explorers.last().ok_or(RuleError::CardNotInZone("explorers"))?;
+                if card.get_cost().unwrap_or(0) > me.hand.trade {
+                    return Err(RuleError::NotEnoughTrade);
+                }
+            }
+            Action::Ally(i) => {
+                let card = me.hand.played.get(i).ok_or(RuleError::CardNotInZone("played cards"))?;
+                if Self::played_abilities(me, i).and_then(|a| a.on_faction.as_ref()).is_none() {
+                    return Err(RuleError::NoSuchAbility(card.get_name()));
+                }
+                if Self::is_ally_used(me, i) {
+                    return Err(RuleError::AbilityAlreadyUsed);
+                }
+                if !Self::has_ally(me, i) {
+                    return Err(RuleError::NoAlly(card.get_name()));
+                }
+            }
+            Action::Scrap(i) => {
+                let card = me.hand.played.get(i).ok_or(RuleError::CardNotInZone("played cards"))?;
+                if Self::played_abilities(me, i).and_then(|a| a.on_scrap.as_ref()).is_none() {
+                    return Err(RuleError::NoSuchAbility(card.get_name()));
+                }
+            }
+            Action::Activate(i) => {
+                if i >= me.hand.abilities.len() {
+                    return Err(RuleError::CardNotInZone("delayed abilities"));
+                }
+            }
+            Action::AttackPlayer => {
+                if me.hand.damage <= 0 {
+                    return Err(RuleError::NotEnoughDamage);
+                }
+                if has_outpost {
+                    return Err(RuleError::OutpostBlocksAttack);
+                }
+            }
+            Action::AttackBase(i) => {
+                let base = opponent_bases.get(i).ok_or(RuleError::CardNotInZone("opponent's bases"))?;
+                if has_outpost && !base.is_outpost() {
+                    return Err(RuleError::OutpostBlocksAttack);
+                }
+                if base.get_defense().unwrap_or(0) > me.hand.damage {
+                    return Err(RuleError::NotEnoughDamage);
+                }
+            }
+            Action::EndTurn | Action::Timeout => {}
+            Action::Choose(_) | Action::Select(_) | Action::Confirm | Action::Decline => {
+                return Err(RuleError::WrongPhase("No decision is pending"));
+            }
+        }
+        Ok(())
+    }
+
+    /// Applies the after capacities that need the engine once `ability` has been used
+    fn after_atomic(gs: GameState, ability: &AtomicAbility) -> GameState {
+        let mut gs = gs;
+        if ability.after_capacity().is_some_and(|c| c.contains(&AfterCapacity::ScrapFromHand)) {
+            gs.update_pending(|p| p.insert(0, Decision::new(&SCRAP_FROM_HAND)));
+        }
+        gs
+    }
+
+    /// Uses an ability for the current player. Choices are queued as pending decisions.
+    pub fn resolve(gs: &GameState, ability: &'static Ability) -> Result<GameState, RuleError> {
+        match ability {
+            Ability::Atomic(a) => match a.ability() {
+                AtomicAbilityFn::Default(f) => Ok(Self::after_atomic(f(gs)?, a)),
+                _ => {
+                    let mut gs = gs.clone();
+                    gs.update_pending(|p| p.push(Decision::new(ability)));
+                    Ok(gs)
+                }
+            },
+            Ability::And(a, b) => Self::resolve(&Self::resolve(gs, a)?, b),
+            Ability::Or(_, _) => {
+                let mut gs = gs.clone();
+                gs.update_pending(|p| p.push(Decision::new(ability)));
+                Ok(gs)
+            }
+            Ability::Cond(pred, a) => {
+                if pred.test(gs) {
+                    Self::resolve(gs, a)
+                } else {
+                    Ok(gs.clone())
+                }
+            }
+            Ability::Delayed(a) => {
+                let mut current_player = gs.get_current_player();
+                current_player.hand.abilities.push(a);
+                gs.mutate_players(current_player, Self::current_id(gs))
+            }
+        }
+    }
+
+    fn apply_decision(gs: &GameState, action: &Action) -> Result<GameState, RuleError> {
+        let mut gs = gs.clone();
+        let mut decision = gs.update_pending(|p| p.remove(0));
+        match (&decision.ability, action) {
+            (Ability::Or(a, b), Action::Choose(i)) => {
+                Self::resolve(&gs, if *i == 0 { a } else { b })
+            }
+            (Ability::Atomic(a), Action::Select(t)) => {
+                if let AtomicAbilityFn::Card(f) = a.ability() {
+                    let card = Self::target_card(&gs, t).ok_or(RuleError::CardNotInZone(t.zone()))?;
+                    return Ok(Self::after_atomic(f(&gs, &card)?, a));
+                }
+                decision.selected.push(*t);
+                gs.update_pending(|p| p.insert(0, decision));
+                Ok(gs)
+            }
+            (Ability::Atomic(a), Action::Confirm) => {
+                let mut hand = vec![];
+                let mut discard = vec![];
+                for t in &decision.selected {
+                    let card = Self::target_card(&gs, t).ok_or(RuleError::CardNotInZone(t.zone()))?;
+                    match t {
+                        Target::Discarded(_) => discard.push(card),
+                        _ => hand.push(card),
+                    }
+                }
+                let new_gs = match a.ability() {
+                    AtomicAbilityFn::Cards(f) => f(&gs, &hand)?,
+                    AtomicAbilityFn::CardsFromHandOrDiscard(f) => f(&gs, &hand, &discard)?,
+                    _ => return Err(RuleError::WrongPhase("Select a card or decline")),
+                };
+                Ok(Self::after_atomic(new_gs, a))
+            }
+            (Ability::Atomic(_) | Ability::Or(_, _), Action::Decline) => Ok(gs),
+            _ => Err(RuleError::WrongPhase("A decision is pending")),
+        }
+    }
+
+    /// Asks the player whose turn begins for the cards the opponent made them discard, then
+    /// uses their played bases
+    fn start_turn(gs: &GameState) -> Result<GameState, RuleError> {
+        let mut current_player = gs.get_current_player();
+        current_player.hand.ally_used = vec![false; current_player.hand.played.len()];
+        let discards = std::mem::take(&mut current_player.hand.discards);
+        let bases = current_player.hand.played.clone();
+        let mut gs = gs.mutate_players(current_player, Self::current_id(gs))?;
+        gs.update_pending(|p| p.extend((0..discards).map(|_| Decision::new(&DISCARD))));
+        for base in bases {
+            if let Some(a) = base.get_abilities().on_board.as_ref() {
+                gs = Self::resolve(&gs, a)?;
+            }
+        }
+        Ok(gs)
+    }
+
+    fn end_turn(gs: &GameState) -> Result<GameState, RuleError> {
+        let mut current_player = gs.get_current_player();
+        let played = std::mem::take(&mut current_player.hand.played);
+        current_player.hand.played = Card::filter_bases(played.clone());
+        current_player.discard.append(&mut Card::filter_ships(played));
+        current_player.discard.append(&mut current_player.hand.playable);
+        current_player.hand.abilities.clear();
+        current_player.hand.ally_used.clear();
+        current_player.hand.trade = 0;
+        current_player.hand.damage = 0;
+        current_player.hand.next_n_ships_on_top = 0;
+        current_player.hand.next_n_ships_free = 0;
+        current_player.hand.damage_per_ship = 0;
+        current_player.hand.copied = None;
+        let me = Self::current_id(gs);
+        let mut gs = gs.mutate_players(current_player, me)?;
+        gs.draw(me, 5)?;
+        gs.emit(Event::TurnEnded { player: me });
+        if let Some(clock) = gs.clock.as_mut() {
+            clock.end_turn(gs.turn % 2);
+        }
+        gs.update_pending(|p| p.clear());
+        gs.next_turn();
+        Self::start_turn(&gs)
+    }
+
+    /// Checks then applies an action, returning the new state
+    pub fn apply(gs: &GameState, action: &Action) -> Result<GameState, RuleError> {
+        Self::step(gs, action).map(|(gs, _)| gs)
+    }
+
+    /// Checks then applies an action, returning the new state and what happened in order
+    pub fn step(gs: &GameState, action: &Action) -> Result<(GameState, Vec<Event>), RuleError> {
+        debug_assert_eq!(gs.zobrist(), gs.full_zobrist(), "state changed without a rehash");
+        Self::check(gs, action)?;
+        let mut next = Self::transition(gs, action)?;
+        debug_assert_eq!(next.zobrist(), next.full_zobrist(), "hash out of date after {action}");
+        if let Some(winner) = next.winner() {
+            next.emit(Event::GameWon { player: winner });
+        }
+        let events = next.take_events();
+        Ok((next, events))
+    }
+
+    fn transition(gs: &GameState, action: &Action) -> Result<GameState, RuleError> {
+        if !gs.pending.is_empty() && *action != Action::Timeout {
+            return Self::apply_decision(gs, action);
+        }
+        let me = Self::current_id(gs);
+        let mut current_player = gs.get_current_player();
+        match *action {
+            Action::Play(i) => {
+                let card = current_player.hand.playable.remove(i);
+                current_player.hand.played.push(card);
+                if !card.is_base() {
+                    current_player.hand.damage += current_player.hand.damage_per_ship;
+                }
+                let mut gs = gs.mutate_players(current_player, me)?;
+                gs.emit(Event::CardPlayed { player: me, card });
+                match card.get_abilities().on_board.as_ref() {
+                    Some(a) => Self::resolve(&gs, a),
+                    None => Ok(gs),
+                }
+            }
+            Action::Buy(i) => {
+                let mut gs = Self::acquire(gs, current_player, Zone::TradeRow, i)?;
+                gs.refill_trade_row(i);
+                Ok(gs)
+            }
+            Action::BuyExplorer => {
+                let at = gs.explorers.len().checked_sub(1).ok_or(RuleError::CardNotInZone("explorers"))?;
+                Self::acquire(gs, current_player, Zone::Explorers, at)
+            }
+            Action::Ally(i) => {
+                let ability = Self::played_abilities(&current_player, i).and_then(|a| a.on_faction.as_ref());
+                let played = current_player.hand.played.len();
+                current_player.hand.ally_used.resize(played, false);
+                current_player.hand.ally_used[i] = true;
+                let gs = gs.mutate_players(current_player, me)?;
+                match ability {
+                    Some(a) => Self::resolve(&gs, a),
+                    None => Ok(gs),
+                }
+            }
+            Action::Scrap(i) => {
+                let ability = Self::played_abilities(&current_player, i).and_then(|a| a.on_scrap.as_ref());
+                if current_player.hand.played[i] == Card::StealthNeedle {
+                    current_player.hand.copied = None;
+                }
+                let mut gs = gs.mutate_players(current_player, me)?;
+                gs.scrap_at(Zone::Played(me), i)?;
+                match ability {
+                    Some(a) => Self::resolve(&gs, a),
+                    None => Ok(gs),
+                }
+            }
+            Action::Activate(i) => {
+                let ability = current_player.hand.abilities.remove(i);
+                Self::resolve(&gs.mutate_players(current_player, me)?, ability)
+            }
+            Action::AttackPlayer => {
+                let mut opponent = gs.get_opponent_player();
+                let amount = current_player.hand.damage;
+                opponent.authority -= amount;
+                current_player.hand.damage = 0;
+                let mut gs = gs.mutate_players(current_player, me)?.mutate_players(opponent, Self::opponent_id(gs))?;
+                gs.emit(Event::DamageDealt { player: Self::opponent_id(&gs), amount });
+                Ok(gs)
+            }
+            Action::AttackBase(i) => {
+                let opponent = Self::opponent_id(gs);
+                let (at, base) = gs.get_player(opponent)?.hand.played.iter()
+                    .enumerate()
+                    .filter(|(_, c)| c.is_base())
+                    .nth(i)
+                    .map(|(at, c)| (at, *c))
+                    .ok_or(RuleError::CardNotInZone("opponent's bases"))?;
+                current_player.hand.damage -= base.get_defense().unwrap_or(0);
+                let mut gs = gs.mutate_players(current_player, me)?;
+                gs.transfer_at(Zone::Played(opponent), at, Zone::Discard(opponent))?;
+                gs.emit(Event::BaseDestroyed { player: opponent, card: base });
+                Ok(gs)
+            }
+            Action::EndTurn => Self::end_turn(gs),
+            Action::Timeout => Self::timeout(gs),
+            Action::Choose(_) | Action::Select(_) | Action::Confirm | Action::Decline => {
+                Err(RuleError::WrongPhase("No decision is pending"))
+            }
+        }
+    }
+
+    /// Takes `elapsed_ms` from the clock of the current player, if the game has one
+    pub fn spend_time(gs: &GameState, elapsed_ms: u64) -> GameState {
+        let mut gs = gs.clone();
+        let current = Self::current_id(&gs);
+        if let Some(clock) = gs.clock.as_mut() {
+            clock.spend(current, elapsed_ms);
+        }
+        gs
+    }
+
+    /// The current player ran out of time: the turn ends, pending decisions included, or the game is lost
+    fn timeout(gs: &GameState) -> Result<GameState, RuleError> {
+        let mut gs = gs.clone();
+        let current = Self::current_id(&gs);
+        let clock = gs.clock.as_mut().ok_or(RuleError::NoClock)?;
+        match clock.control.on_timeout {
+            TimeoutAction::EndTurn => {
+                gs.update_pending(|p| p.clear());
+                Self::end_turn(&gs)
+            }
+            TimeoutAction::Forfeit => {
+                clock.flagged = Some(current);
+                Ok(gs)
+            }
+        }
+    }
+
+    /// Pays for the card at `at` in `from` and puts it in the discard pile, or on top of the deck
+    /// when allowed
+    fn acquire(gs: &GameState, current_player: Player, from: Zone, at: usize) -> Result<GameState, RuleError> {
+        let mut current_player = current_player;
+        let me = Self::current_id(gs);
+        let card = *gs.zone(from).get(at).ok_or(RuleError::CardNotInZone(from.name()))?;
+        let ship = !card.is_base();
+        if ship && current_player.hand.next_n_ships_free > 0 {
+            current_player.hand.next_n_ships_free -= 1;
+        } else {
+            current_player.hand.trade -= card.get_cost().unwrap_or(0);
+        }
+        let to = if ship && current_player.hand.next_n_ships_on_top > 0 {
+            current_player.hand.next_n_ships_on_top -= 1;
+            Zone::Deck(me)
+        } else {
+            Zone::Discard(me)
+        };
+        let mut gs = gs.mutate_players(current_player, me)?;
+        gs.transfer_at(from, at, to)?;
+        gs.emit(Event::CardAcquired { player: me, card });
+        Ok(gs)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_play_and_end_turn() {
+        let gs = GameState::with_seed(7);
+        assert_eq!(gs.players.0.hand.playable.len(), 3);
+        assert_eq!(gs.players.1.hand.playable.len(), 5);
+        let mut gs = gs;
+        while !gs.players.0.hand.playable.is_empty() {
+            gs = GameLogic::apply(&gs, &Action::Play(0)).unwrap();
+        }
+        assert_eq!(gs.players.0.hand.played.len(), 3);
+        let gs = GameLogic::apply(&gs, &Action::EndTurn).unwrap();
+        assert_eq!(gs.turn, 1);
+        assert_eq!(gs.players.0.hand.playable.len(), 5);
+        assert_eq!(gs.players.0.discard.len(), 3);
+        assert_eq!(gs.players.0.hand.trade, 0);
+    }
+
+    #[test]
+    fn test_outpost_blocks_attack() {
+        let mut gs = GameState::with_seed(1);
+        gs.players.1.hand.played.push(Card::SpaceStation);
+        gs.players.0.hand.damage = 5;
+        gs.rehash();
+        assert_eq!(GameLogic::check(&gs, &Action::AttackPlayer), Err(RuleError::OutpostBlocksAttack));
+        let gs = GameLogic::apply(&gs, &Action::AttackBase(0)).unwrap();
+        assert_eq!(gs.players.0.hand.damage, 1);
+        assert_eq!(gs.players.1.discard.len(), 1);
+        assert!(GameLogic::check(&gs, &Action::AttackPlayer).is_ok());
+    }
+
+    #[test]
+    fn test_states_move_between_threads() {
+        let gs = GameState::with_seed(2);
+        let played = std::thread::spawn(move || GameLogic::apply(&gs, &Action::Play(0))).join().unwrap().unwrap();
+        assert_eq!(played.players.0.hand.played.len(), 1);
+    }
+
+    #[test]
+    fn test_rule_errors() {
+        let gs = GameState::with_seed(1);
+        assert_eq!(GameLogic::check(&gs, &Action::Play(7)), Err(RuleError::CardNotInZone("hand")));
+        assert_eq!(GameLogic::check(&gs, &Action::Buy(0)), Err(RuleError::NotEnoughTrade));
+        assert_eq!(GameLogic::check(&gs, &Action::AttackPlayer), Err(RuleError::NotEnoughDamage));
+        assert_eq!(GameLogic::check(&gs, &Action::Confirm), Err(RuleError::WrongPhase("No decision is pending")));
+        assert_eq!(GameLogic::check(&gs, &Action::Timeout), Err(RuleError::NoClock));
+        assert_eq!(gs.mutate_players(gs.players.0.clone(), 2).err(), Some(RuleError::InvalidPlayer(2)));
+        assert_eq!(gs.get_player(2).err(), Some(RuleError::InvalidPlayer(2)));
+        assert_eq!(gs.get_player(-1).err(), Some(RuleError::InvalidPlayer(-1)));
+        assert_eq!(gs.clone().draw(3, 1), Err(RuleError::InvalidPlayer(3)));
+
+        let mut over = gs.clone();
+        over.players.1.authority = 0;
+        assert_eq!(GameLogic::check(&over, &Action::EndTurn), Err(RuleError::GameOver));
+    }
+
+    #[test]
+    fn test_events() {
+        let gs = GameState::with_seed(7);
+        let (gs, events) = GameLogic::step(&gs, &Action::Play(0)).unwrap();
+        let card = gs.players.0.hand.played[0];
+        assert_eq!(events, vec![Event::CardPlayed { player: 0, card }]);
+        let (_, events) = GameLogic::step(&gs, &Action::EndTurn).unwrap();
+        assert_eq!(events, vec![Event::CardsDrawn { player: 0, count: 5 }, Event::TurnEnded { player: 0 }]);
+
+        let mut gs = GameState::with_seed(1);
+        gs.players.0.hand.damage = 60;
+        gs.rehash();
+        let (_, events) = GameLogic::step(&gs, &Action::AttackPlayer).unwrap();
+        assert_eq!(events, vec![Event::DamageDealt { player: 1, amount: 60 }, Event::GameWon { player: 0 }]);
+    }
+
+    #[test]
+    fn test_zone_transfers() {
+        let mut gs = GameState::with_seed(3);
+        let card = gs.players.0.hand.playable[0];
+        let stolen = gs.transfer(card, Zone::Hand(0), Zone::Discard(1));
+        assert_eq!(stolen, Err(RuleError::InvalidTransfer(card.get_name(), Zone::Hand(0), Zone::Discard(1))));
+        assert_eq!(gs.transfer(card, Zone::Hand(0), Zone::Discard(2)), Err(RuleError::InvalidPlayer(2)));
+        assert!(gs.zone(Zone::Hand(2)).is_empty());
+        assert_eq!(gs.transfer(card, Zone::Hand(0), Zone::Discard(0)), Ok(()));
+        assert_eq!(gs.players.0.discard, vec![card]);
+        assert_eq!(gs.transfer(Card::Explorer, Zone::Hand(0), Zone::Scrap), Err(RuleError::CardNotInZone("hand")));
+        assert!(gs.transfer(Card::Explorer, Zone::Explorers, Zone::Scrap).is_err());
+        let row = gs.trade_row[0];
+        assert!(gs.transfer(row, Zone::TradeRow, Zone::Explorers).is_err());
+        assert_eq!(gs.scrap_at(Zone::TradeRow, 0), Ok(row));
+        assert!(gs.transfer(row, Zone::Scrap, Zone::Hand(0)).is_err());
+    }
+
+    #[test]
+    fn test_random_playout_terminates() {
+        use rand::prelude::{SeedableRng, SliceRandom, StdRng};
+        let mut rng = StdRng::seed_from_u64(3);
+        let mut gs = GameState::with_seed(3);
+        for _ in 0..20_000 {
+            if gs.is_over() {
+                break;
+            }
+            let actions = GameLogic::legal_actions(&gs);
+            // Attacking as soon as possible keeps random games short
+            let action = if actions.contains(&Action::AttackPlayer) {
+                Action::AttackPlayer
+            } else {
+                *actions.choose(&mut rng).unwrap()
+            };
+            gs = GameLogic::apply(&gs, &action).unwrap();
+        }
+        assert!(gs.is_over());
+    }
+}
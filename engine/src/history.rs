@@ -0,0 +1,132 @@
+use crate::gamelogic::{Action, GameLogic};
+use crate::star_realms::GameState;
+
+/// Undo and redo of the actions of the current turn. States are never changed in place, so
+/// going back is giving back an older state.
+///
+/// Actions that reveal hidden information (a draw, a shuffle, a refill of the trade row, a change
+/// to the opponent's hand or the next turn) cannot be undone: they clear the history. Frontends ask
+/// the player to confirm them first, see `needs_confirmation`.
+#[derive(Debug, Clone, Default)]
+pub struct History {
+    /// Each action of the turn with the state it was played from, the last one on top
+    undo: Vec<(Action, GameState)>,
+    /// Each undone action with the state it led to
+    redo: Vec<(Action, GameState)>,
+}
+
+/// Whether going from `before` to `after` showed cards the current player did not know
+pub fn reveals(before: &GameState, after: &GameState) -> bool {
+    before.turn != after.turn
+        || before.trade_deck.len() != after.trade_deck.len()
+        || before.players.0.deck.len() != after.players.0.deck.len()
+        || before.players.1.deck.len() != after.players.1.deck.len()
+        || before.opponent_player().hand.playable != after.opponent_player().hand.playable
+}
+
+impl History {
+    pub fn new() -> History {
+        History::default()
+    }
+
+    /// Remembers that `action` led from `before` to `after`, or forgets everything when it revealed cards
+    pub fn record(&mut self, action: Action, before: &GameState, after: &GameState) {
+        self.redo.clear();
+        if reveals(before, after) {
+            self.undo.clear();
+        } else {
+            self.undo.push((action, before.clone()));
+        }
+    }
+
+    /// Whether the player should confirm `action`: it would reveal cards while some actions can still be undone.
+    /// Ending the turn is never asked for.
+    pub fn needs_confirmation(&self, gs: &GameState, action: &Action) -> bool {
+        self.can_undo() && *action != Action::EndTurn
+            && GameLogic::apply(gs, action).is_ok_and(|next| reveals(gs, &next))
+    }
+
+    pub fn can_undo(&self) -> bool {
+        !self.undo.is_empty()
+    }
+
+    pub fn can_redo(&self) -> bool {
+        !self.redo.is_empty()
+    }
+
+    /// Takes back the last action, which led to `current`. Gives the action and the state before it.
+    pub fn undo(&mut self, current: &GameState) -> Option<(Action, GameState)> {
+        let (action, before) = self.undo.pop()?;
+        self.redo.push((action, current.clone()));
+        Some((action, before))
+    }
+
+    /// Plays again the last undone action from `current`. Gives the action and the state after it.
+    pub fn redo(&mut self, current: &GameState) -> Option<(Action, GameState)> {
+        let (action, after) = self.redo.pop()?;
+        self.undo.push((action, current.clone()));
+        Some((action, after))
+    }
+
+    pub fn clear(&mut self) {
+        self.undo.clear();
+        self.redo.clear();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cards::Card;
+
+    fn play(history: &mut History, gs: &GameState, action: Action) -> GameState {
+        let next = GameLogic::apply(gs, &action).unwrap();
+        history.record(action, gs, &next);
+        next
+    }
+
+    #[test]
+    fn test_undo_redo_within_a_turn() {
+        let mut history = History::new();
+        let start = GameState::with_seed(4);
+        let played = play(&mut history, &start, Action::Play(0));
+        let played = play(&mut history, &played, Action::Play(0));
+        assert!(!history.can_redo());
+
+        let (action, back) = history.undo(&played).unwrap();
+        assert_eq!(action, Action::Play(0));
+        assert_eq!(back.get_player(0).unwrap().hand.playable.len(), start.get_player(0).unwrap().hand.playable.len() - 1);
+        let (_, forward) = history.redo(&back).unwrap();
+        assert_eq!(forward.encode(0), played.encode(0));
+
+        // buying from the trade row refills it
+        let mut gs = played;
+        while GameLogic::legal_actions(&gs).contains(&Action::Play(0)) {
+            gs = play(&mut history, &gs, Action::Play(0));
+        }
+        let buy = GameLogic::legal_actions(&gs).into_iter().find(|a| matches!(a, Action::Buy(_))).unwrap();
+        assert!(history.needs_confirmation(&gs, &buy));
+        assert!(!history.needs_confirmation(&gs, &Action::BuyExplorer));
+        assert!(!history.needs_confirmation(&gs, &Action::EndTurn));
+        let next_turn = play(&mut history, &gs, Action::EndTurn);
+        assert!(!history.can_undo() && history.undo(&next_turn).is_none());
+    }
+
+    #[test]
+    fn test_changes_to_the_opponent_hand_cannot_be_undone() {
+        let start = GameState::with_seed(4);
+        let mut discarded = start.clone();
+        let card = discarded.players.1.hand.playable.remove(0);
+        discarded.players.1.discard.push(card);
+        discarded.rehash();
+        assert!(reveals(&start, &discarded));
+
+        // the opponent only discards on their turn
+        let mut history = History::new();
+        let mut gs = start;
+        gs.players.0.hand.playable.push(Card::ImperialFighter);
+        gs.rehash();
+        play(&mut history, &gs, Action::Play(gs.players.0.hand.playable.len() - 1));
+        assert!(history.can_undo());
+    }
+}
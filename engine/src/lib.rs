@@ -0,0 +1,18 @@
+pub mod star_realms;
+pub mod abilities;
+pub mod cards;
+pub mod gamelogic;
+pub mod env;
+pub mod agents;
+pub mod tournament;
+pub mod replay;
+pub mod view;
+pub mod clock;
+pub mod history;
+pub mod error;
+pub mod events;
+pub mod zobrist;
+pub mod analysis;
+pub mod purchases;
+#[cfg(feature = "serde")]
+pub mod protocol;
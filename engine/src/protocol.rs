@@ -0,0 +1,51 @@
+//! Messages between the game server and its clients, sent as JSON over a WebSocket.
+//!
+//! A client first sends `Create`, `Join`, `Spectate` or `Reconnect` and gets `Joined` back,
+//! then a `State` after every change of the game. Players send their moves with `Action`.
+use serde::{Deserialize, Serialize};
+use crate::clock::TimeControl;
+use crate::gamelogic::Action;
+use crate::view::TableView;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ClientMessage {
+    /// Opens a lobby and takes its first seat. The clock starts when the second player sits.
+    Create {
+        #[serde(default)]
+        clock: Option<TimeControl>,
+    },
+    /// Takes the free seat of a lobby
+    Join { code: String },
+    Spectate { code: String },
+    /// Takes back a seat after the connection was lost
+    Reconnect { code: String, token: String },
+    Action { action: Action },
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ServerMessage {
+    /// `seat` and the `token` needed to reconnect are missing for spectators
+    Joined { code: String, seat: Option<i32>, token: Option<String> },
+    /// The game, as seen by the receiver
+    State { view: Box<TableView> },
+    /// A player left or came back
+    Presence { seat: i32, connected: bool },
+    Error { message: String },
+}
+
+/// Cards as their names, for the fields of messages and events that hold a `Card`
+pub(crate) mod card_name {
+    use serde::{de, Deserialize, Deserializer, Serializer};
+    use crate::cards::{Card, CardFactory};
+
+    pub fn serialize<S: Serializer>(card: &Card, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&card.get_name())
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Card, D::Error> {
+        let name = String::deserialize(deserializer)?;
+        CardFactory::by_name(&name).ok_or_else(|| de::Error::custom(format!("Unknown card: {name}")))
+    }
+}
@@ -0,0 +1,411 @@
+use std::fmt::{Display, Formatter};
+use std::io::Write;
+use crate::cards::{Card, Faction};
+use crate::events::Event;
+use crate::tournament::{play_game, run_parallel, Entrant};
+
+/// Part of the game a purchase was made in, from the number of turns its buyer played
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Phase {
+    Early,
+    Middle,
+    Late,
+}
+
+impl Phase {
+    pub const ALL: [Phase; 3] = [Phase::Early, Phase::Middle, Phase::Late];
+
+    /// Phase of the game turn `turn`, each player having played about half of the turns
+    pub fn of_turn(turn: i32) -> Phase {
+        match turn / 2 + 1 {
+            ..=4 => Phase::Early,
+            5..=10 => Phase::Middle,
+            _ => Phase::Late,
+        }
+    }
+
+    fn index(&self) -> usize {
+        *self as usize
+    }
+}
+
+impl Display for Phase {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Phase::Early => write!(f, "early game (turns 1-4)"),
+            Phase::Middle => write!(f, "mid game (turns 5-10)"),
+            Phase::Late => write!(f, "late game (turns 11+)"),
+        }
+    }
+}
+
+/// A card acquired during a game, with what its buyer owned before
+#[derive(Debug, Clone)]
+pub struct Purchase {
+    pub game: usize,
+    pub player: i32,
+    pub turn: i32,
+    pub card: Card,
+    /// Cards owned for each faction, as given by `Analysis::composition`
+    pub composition: Vec<(Option<Faction>, usize)>,
+    /// Whether the buyer won the game, `None` for draws
+    pub won: Option<bool>,
+}
+
+impl Purchase {
+    pub fn phase(&self) -> Phase {
+        Phase::of_turn(self.turn)
+    }
+
+    /// Number of factions the buyer owned cards of
+    pub fn factions(&self) -> usize {
+        self.composition.iter().filter(|(f, n)| f.is_some() && *n > 0).count()
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct PurchaseConfig {
+    /// Games played, cycling over every ordered pair of agents, self-play included
+    pub games: usize,
+    pub seed: u64,
+    /// Worker threads, all the available cores when 0
+    pub threads: usize,
+    /// Games still running after this many turns are draws
+    pub max_turns: i32,
+    /// Strength of the L2 regularization of the fit, pulling rare cards to no effect
+    pub regularization: f64,
+}
+
+impl Default for PurchaseConfig {
+    fn default() -> Self {
+        PurchaseConfig {
+            games: 1000,
+            seed: 0,
+            threads: 0,
+            max_turns: 200,
+            regularization: 0.01,
+        }
+    }
+}
+
+/// Agents and outcome of a game, agents are given by index
+#[derive(Debug, Clone)]
+pub struct GameLog {
+    pub agents: [usize; 2],
+    pub winner: Option<i32>,
+    pub turns: i32,
+}
+
+/// Plays one game, `agents[0]` moving first, and logs every card acquired
+fn play_logged(entrants: &[Entrant], agents: [usize; 2], game: usize, seed: u64, max_turns: i32) -> (GameLog, Vec<Purchase>) {
+    let mut first = (entrants[agents[0]].factory)(seed);
+    let mut second = (entrants[agents[1]].factory)(seed.wrapping_add(1));
+    let mut purchases = vec![];
+    let (winner, turns) = play_game((first.as_mut(), second.as_mut()), seed, max_turns, |gs, events| {
+        for event in events {
+            if let Event::CardAcquired { player, card } = *event {
+                let composition = gs.get_player(player).map(|p| p.analysis().composition()).unwrap_or_default();
+                purchases.push(Purchase { game, player, turn: gs.turn, card, composition, won: None });
+            }
+        }
+    });
+    for purchase in purchases.iter_mut() {
+        purchase.won = winner.map(|w| w == purchase.player);
+    }
+    (GameLog { agents, winner, turns }, purchases)
+}
+
+/// Fitted worth of a card bought in a phase of the game
+#[derive(Debug, Clone)]
+pub struct CardValue {
+    pub card: Card,
+    pub phase: Phase,
+    pub purchases: usize,
+    /// Games won by the players who bought the card in this phase, over the games they finished
+    pub win_rate: f64,
+    /// Change of the log-odds of winning for each copy bought, the agent, the seat and the other
+    /// purchases being equal
+    pub coefficient: f64,
+}
+
+impl CardValue {
+    /// Change of the win rate from an even game for each copy bought
+    pub fn contribution(&self) -> f64 {
+        1. / (1. + (-self.coefficient).exp()) - 0.5
+    }
+}
+
+/// Index of the weight of `card` bought in `phase`
+fn feature(card: Card, phase: Phase) -> usize {
+    phase.index() * Card::ALL.len() + card.index()
+}
+
+const CARD_FEATURES: usize = 3 * Card::ALL.len();
+
+/// Logistic regression of winning over the copies of each card bought in each phase, with one
+/// sample by player of each finished game. The agent of the player and whether they moved first
+/// are covariates, so that cards are not credited for the strength of the agents buying them.
+/// Card weights are regularized, the rest is fitted freely, by Newton's method.
+fn fit(games: &[GameLog], purchases: &[Purchase], agents: usize, regularization: f64) -> Vec<f64> {
+    let features = CARD_FEATURES + agents + 1;
+    let mut samples: Vec<(Vec<(usize, f64)>, f64)> = vec![];
+    let mut index = vec![[None; 2]; games.len()];
+    for (g, game) in games.iter().enumerate() {
+        let Some(winner) = game.winner else { continue };
+        for (player, (agent, sample)) in game.agents.iter().zip(index[g].iter_mut()).enumerate() {
+            let mut x = vec![(CARD_FEATURES + agent, 1.)];
+            if player == 0 {
+                x.push((features - 1, 1.));
+            }
+            *sample = Some(samples.len());
+            samples.push((x, if winner == player as i32 { 1. } else { 0. }));
+        }
+    }
+    for p in purchases {
+        let Some(i) = index[p.game][p.player as usize] else { continue };
+        let x = &mut samples[i].0;
+        let f = feature(p.card, p.phase());
+        match x.iter_mut().find(|(j, _)| *j == f) {
+            Some((_, count)) => *count += 1.,
+            None => x.push((f, 1.)),
+        }
+    }
+    let mut weights = vec![0.; features];
+    if samples.is_empty() {
+        return weights;
+    }
+    let n = samples.len() as f64;
+    // cards never bought only have the penalty, the small one of the covariates keeps them defined
+    let penalty = |j: usize| if j < CARD_FEATURES { regularization } else { 1e-6 };
+    for _ in 0..25 {
+        let mut gradient: Vec<f64> = (0..features).map(|j| penalty(j) * weights[j]).collect();
+        let mut hessian = vec![vec![0.; features]; features];
+        for (j, row) in hessian.iter_mut().enumerate() {
+            row[j] = penalty(j);
+        }
+        for (x, y) in &samples {
+            let z: f64 = x.iter().map(|&(j, v)| v * weights[j]).sum();
+            let p = 1. / (1. + (-z).exp());
+            for &(j, v) in x {
+                gradient[j] += (p - y) * v / n;
+                for &(k, u) in x {
+                    hessian[j][k] += p * (1. - p) * v * u / n;
+                }
+            }
+        }
+        let step = solve(hessian, gradient);
+        for (w, s) in weights.iter_mut().zip(&step) {
+            *w -= s;
+        }
+        if step.iter().all(|s| s.abs() < 1e-9) {
+            break;
+        }
+    }
+    weights
+}
+
+/// Solves `a x = b` by Gaussian elimination, `a` being symmetric positive definite
+fn solve(mut a: Vec<Vec<f64>>, mut b: Vec<f64>) -> Vec<f64> {
+    let n = b.len();
+    for i in 0..n {
+        let (pivot, rest) = a[i..].split_first_mut().unwrap();
+        for (r, row) in rest.iter_mut().enumerate() {
+            let factor = row[i] / pivot[i];
+            if factor == 0. {
+                continue;
+            }
+            for (cell, p) in row[i..].iter_mut().zip(&pivot[i..]) {
+                *cell -= factor * p;
+            }
+            b[i + 1 + r] -= factor * b[i];
+        }
+    }
+    let mut x = vec![0.; n];
+    for i in (0..n).rev() {
+        let sum: f64 = (i + 1..n).map(|c| a[i][c] * x[c]).sum();
+        x[i] = (b[i] - sum) / a[i][i];
+    }
+    x
+}
+
+#[derive(Debug, Clone)]
+pub struct PurchaseReport {
+    pub agents: Vec<String>,
+    pub games: Vec<GameLog>,
+    pub purchases: Vec<Purchase>,
+    pub values: Vec<CardValue>,
+}
+
+impl PurchaseReport {
+    pub fn new(agents: Vec<String>, games: Vec<GameLog>, purchases: Vec<Purchase>, regularization: f64) -> PurchaseReport {
+        let weights = fit(&games, &purchases, agents.len(), regularization);
+        let mut values = vec![];
+        for phase in Phase::ALL {
+            for card in Card::ALL {
+                let bought: Vec<&Purchase> = purchases.iter().filter(|p| p.card == card && p.phase() == phase).collect();
+                if bought.is_empty() {
+                    continue;
+                }
+                let finished = bought.iter().filter(|p| p.won.is_some()).count();
+                let wins = bought.iter().filter(|p| p.won == Some(true)).count();
+                values.push(CardValue {
+                    card,
+                    phase,
+                    purchases: bought.len(),
+                    win_rate: if finished == 0 { 0. } else { wins as f64 / finished as f64 },
+                    coefficient: weights[feature(card, phase)],
+                });
+            }
+        }
+        values.sort_by(|a, b| b.coefficient.total_cmp(&a.coefficient));
+        PurchaseReport { agents, games, purchases, values }
+    }
+
+    /// Values of the cards bought in `phase`, best first
+    pub fn ranking(&self, phase: Phase) -> impl Iterator<Item = &CardValue> {
+        self.values.iter().filter(move |v| v.phase == phase)
+    }
+
+    /// Writes every purchase as a CSV line with the context it was made in
+    pub fn write_log(&self, out: &mut impl Write) -> std::io::Result<()> {
+        writeln!(out, "game,player,agent,turn,phase,card,cost,unaligned,blob,machine,star,trade,factions,won")?;
+        for p in &self.purchases {
+            let agent = &self.agents[self.games[p.game].agents[p.player as usize]];
+            let composition: Vec<String> = p.composition.iter().map(|(_, n)| n.to_string()).collect();
+            let won = p.won.map_or(String::new(), |w| (w as u8).to_string());
+            writeln!(out, "{},{},{},{},{:?},{},{},{},{},{}",
+                     p.game, p.player, agent, p.turn, p.phase(), p.card.get_name(), cost(p.card),
+                     composition.join(","), p.factions(), won)?;
+        }
+        Ok(())
+    }
+}
+
+fn cost(card: Card) -> String {
+    card.get_cost().map_or(String::new(), |c| c.to_string())
+}
+
+/// Ranked table of the cards for each phase of the game
+impl Display for PurchaseReport {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} purchases over {} games of {}", self.purchases.len(), self.games.len(), self.agents.join(", "))?;
+        for phase in Phase::ALL {
+            writeln!(f, "\n\n{phase}")?;
+            write!(f, "{:<20} {:>5} {:>9} {:>8} {:>13}", "card", "cost", "bought", "win %", "value / copy")?;
+            for v in self.ranking(phase) {
+                write!(f, "\n{:<20} {:>5} {:>9} {:>7.1}% {:>+12.1}%",
+                       v.card.get_name(), cost(v.card), v.purchases, v.win_rate * 100., v.contribution() * 100.)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Self-play games between agents, logging the cards they buy to estimate what each card is worth
+pub struct PurchaseStudy {
+    pub agents: Vec<Entrant>,
+    pub config: PurchaseConfig,
+}
+
+impl PurchaseStudy {
+    pub fn new(agents: Vec<Entrant>, config: PurchaseConfig) -> PurchaseStudy {
+        PurchaseStudy {
+            agents,
+            config,
+        }
+    }
+
+    /// Every game to play as (agents, seed)
+    fn schedule(&self) -> Vec<([usize; 2], u64)> {
+        let n = self.agents.len();
+        (0..self.config.games)
+            .map(|g| {
+                let seed = self.config.seed
+                    .wrapping_mul(6364136223846793005)
+                    .wrapping_add((g as u64).wrapping_mul(1442695040888963407));
+                ([g % n, (g / n) % n], seed)
+            })
+            .collect()
+    }
+
+    pub fn run(&self) -> PurchaseReport {
+        let jobs: Vec<(usize, ([usize; 2], u64))> = self.schedule().into_iter().enumerate().collect();
+        let logs = run_parallel(&jobs, self.config.threads, |&(game, (agents, seed))| {
+            play_logged(&self.agents, agents, game, seed, self.config.max_turns)
+        });
+        let (games, purchases): (Vec<GameLog>, Vec<Vec<Purchase>>) = logs.into_iter().unzip();
+        let names = self.agents.iter().map(|e| e.name.clone()).collect();
+        PurchaseReport::new(names, games, purchases.into_iter().flatten().collect(), self.config.regularization)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::agents::{GreedyAgent, RandomAgent};
+
+    fn purchase(game: usize, player: i32, card: Card, won: bool) -> Purchase {
+        Purchase { game, player, turn: 12, card, composition: vec![], won: Some(won) }
+    }
+
+    #[test]
+    fn test_fit() {
+        // Battle Pods win, Cutters lose, Scouts are bought by everyone, half of the games are
+        // won by the second player
+        let mut games = vec![];
+        let mut purchases = vec![];
+        for game in 0..40 {
+            let (winner, loser) = if game % 2 == 0 { (0, 1) } else { (1, 0) };
+            games.push(GameLog { agents: [0, 0], winner: Some(winner), turns: 30 });
+            for (player, card) in [(winner, Card::BattlePod), (winner, Card::Scout), (loser, Card::Cutter), (loser, Card::Scout)] {
+                purchases.push(purchase(game, player, card, player == winner));
+            }
+        }
+        let report = PurchaseReport::new(vec!["greedy".to_string()], games, purchases, 0.01);
+        let ranking: Vec<Card> = report.ranking(Phase::Middle).map(|v| v.card).collect();
+        assert_eq!(ranking, vec![Card::BattlePod, Card::Scout, Card::Cutter]);
+        assert!(report.values[0].contribution() > 0.2);
+        assert!(report.values[1].coefficient.abs() < 1e-6);
+        assert_eq!(report.values[2].win_rate, 0.);
+    }
+
+    #[test]
+    fn test_stronger_agent() {
+        // the first agent wins every game, the cards bought only tell the agents apart
+        let mut games = vec![];
+        let mut purchases = vec![];
+        for game in 0..40 {
+            let agents = if game % 2 == 0 { [0, 1] } else { [1, 0] };
+            let strong = if game % 2 == 0 { 0 } else { 1 };
+            games.push(GameLog { agents, winner: Some(strong), turns: 30 });
+            purchases.push(purchase(game, strong, Card::Cutter, true));
+            if game % 4 < 2 {
+                purchases.push(purchase(game, strong, Card::BattlePod, true));
+                purchases.push(purchase(game, 1 - strong, Card::BattlePod, false));
+            }
+        }
+        let names = vec!["greedy".to_string(), "random".to_string()];
+        let report = PurchaseReport::new(names, games, purchases, 0.01);
+        assert!(report.ranking(Phase::Middle).all(|v| v.contribution().abs() < 0.05));
+    }
+
+    #[test]
+    fn test_study() {
+        let agents = vec![
+            Entrant::new("greedy", Box::new(|_| Box::new(GreedyAgent))),
+            Entrant::new("random", Box::new(|seed| Box::new(RandomAgent::new(seed)))),
+        ];
+        let config = PurchaseConfig { games: 8, seed: 3, threads: 2, max_turns: 100, ..Default::default() };
+        let report = PurchaseStudy::new(agents, config).run();
+        assert_eq!(report.games.len(), 8);
+        assert_eq!(report.games.iter().filter(|g| g.agents == [0, 0]).count(), 2);
+        assert!(!report.purchases.is_empty());
+        assert!(report.purchases.iter().all(|p| p.game < 8 && p.turn % 2 == p.player));
+        // the starting deck holds 8 Scouts and 2 Vipers
+        let first = report.purchases.iter().find(|p| p.turn < 2).unwrap();
+        assert_eq!(first.composition.iter().map(|(_, n)| n).sum::<usize>(), 10);
+        let mut log = vec![];
+        report.write_log(&mut log).unwrap();
+        assert_eq!(String::from_utf8(log).unwrap().lines().count(), report.purchases.len() + 1);
+    }
+}
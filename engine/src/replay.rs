@@ -0,0 +1,280 @@
+use std::fmt::{Display, Formatter};
+use std::str::FromStr;
+use crate::cards::Card;
+use crate::clock::{Clock, TimeControl};
+use crate::error::RuleError;
+use crate::events::Event;
+use crate::gamelogic::{Action, GameLogic};
+use crate::star_realms::GameState;
+
+pub const REPLAY_VERSION: u32 = 1;
+const REPLAY_HEADER: &str = "star-realms replay";
+
+/// One action of a recorded game
+#[derive(Debug, Clone, PartialEq)]
+pub struct ReplayStep {
+    pub turn: i32,
+    pub action: Action,
+    /// Names of the cards chance brought into the hand or the trade row during the action
+    pub revealed: Vec<String>,
+    /// Time the player took before acting, in games with a clock
+    pub elapsed_ms: Option<u64>,
+}
+
+/// A whole game: the seed every shuffle derives from, who played and every action taken.
+///
+/// The text format is a header followed by one line per step:
+/// ```text
+/// star-realms replay 1
+/// seed 42
+/// players greedy,random
+/// 0 play 0 |
+/// 0 end | Scout,Scout,Viper,Scout,Scout
+/// ```
+/// Games with a time control have a `clock 300+5 end` line after the players, and the
+/// milliseconds each action took after a second `|`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Replay {
+    pub seed: u64,
+    pub players: [String; 2],
+    /// Time control of the game, if it was played with a clock
+    pub clock: Option<TimeControl>,
+    pub steps: Vec<ReplayStep>,
+}
+
+/// Names of the cards of `after` that were not in `before`
+fn arrived(before: &[Card], after: &[Card]) -> Vec<String> {
+    let mut remaining: Vec<String> = before.iter().map(|c| c.get_name()).collect();
+    let mut arrived = vec![];
+    for name in after.iter().map(|c| c.get_name()) {
+        match remaining.iter().position(|n| *n == name) {
+            Some(i) => { remaining.remove(i); }
+            None => arrived.push(name),
+        }
+    }
+    arrived
+}
+
+/// Cards chance revealed to the player who acted between two states
+fn revealed(before: &GameState, after: &GameState) -> Vec<String> {
+    let player = before.turn % 2;
+    let hand_before: &[Card] = if after.turn != before.turn {
+        // the whole hand is discarded before drawing a new one
+        &[]
+    } else {
+        &before.current_player().hand.playable
+    };
+    let hand_after = after.get_player(player).map_or(&[][..], |p| &p.hand.playable);
+    let mut cards = arrived(hand_before, hand_after);
+    cards.append(&mut arrived(&before.trade_row, &after.trade_row));
+    cards
+}
+
+impl Replay {
+    pub fn new(seed: u64, players: [String; 2]) -> Replay {
+        Replay {
+            seed,
+            players,
+            clock: None,
+            steps: vec![],
+        }
+    }
+
+    pub fn initial_state(&self) -> GameState {
+        let mut gs = GameState::with_seed(self.seed);
+        gs.clock = self.clock.map(Clock::new);
+        gs
+    }
+
+    /// Applies `action` to `gs` and records it
+    pub fn apply(&mut self, gs: &GameState, action: Action) -> Result<GameState, RuleError> {
+        self.record(gs, action, None).map(|(gs, _)| gs)
+    }
+
+    /// Like `apply`, also giving what happened
+    pub fn step(&mut self, gs: &GameState, action: Action) -> Result<(GameState, Vec<Event>), RuleError> {
+        self.record(gs, action, None)
+    }
+
+    /// Charges the current player's clock with `elapsed_ms`, then applies `action` and records both
+    pub fn apply_timed(&mut self, gs: &GameState, action: Action, elapsed_ms: u64) -> Result<GameState, RuleError> {
+        self.record(&GameLogic::spend_time(gs, elapsed_ms), action, Some(elapsed_ms)).map(|(gs, _)| gs)
+    }
+
+    fn record(&mut self, gs: &GameState, action: Action, elapsed_ms: Option<u64>) -> Result<(GameState, Vec<Event>), RuleError> {
+        let (next, events) = GameLogic::step(gs, &action)?;
+        self.steps.push(ReplayStep {
+            turn: gs.turn,
+            action,
+            revealed: revealed(gs, &next),
+            elapsed_ms,
+        });
+        Ok((next, events))
+    }
+
+    /// Index of the state where `turn` begins, for a state list built by `replay`
+    pub fn turn_start(&self, turn: i32) -> Option<usize> {
+        if turn == 0 {
+            return Some(0);
+        }
+        self.steps.iter().position(|s| s.turn == turn).or_else(|| {
+            // the last recorded turn may have no action yet
+            self.steps.last()
+                .filter(|s| s.turn + 1 == turn && matches!(s.action, Action::EndTurn | Action::Timeout))
+                .map(|_| self.steps.len())
+        })
+    }
+
+    /// The same game stopped after `steps` actions, to keep playing from there
+    pub fn truncated(&self, steps: usize) -> Replay {
+        Replay {
+            seed: self.seed,
+            players: self.players.clone(),
+            clock: self.clock,
+            steps: self.steps[..steps.min(self.steps.len())].to_vec(),
+        }
+    }
+}
+
+/// Rebuilds every intermediate state of a recorded game, the initial one first.
+/// Fails if an action is illegal or chance does not reveal the recorded cards.
+pub fn replay(replay: &Replay) -> Result<Vec<GameState>, String> {
+    let mut states = vec![replay.initial_state()];
+    for (i, step) in replay.steps.iter().enumerate() {
+        let gs = &GameLogic::spend_time(states.last().unwrap(), step.elapsed_ms.unwrap_or(0));
+        let next = GameLogic::apply(gs, &step.action).map_err(|e| format!("Step {i} ({}): {e}", step.action))?;
+        if revealed(gs, &next) != step.revealed {
+            return Err(format!("Step {i} ({}): the game diverged from the recording", step.action));
+        }
+        states.push(next);
+    }
+    Ok(states)
+}
+
+impl Display for Replay {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        writeln!(f, "{REPLAY_HEADER} {REPLAY_VERSION}")?;
+        writeln!(f, "seed {}", self.seed)?;
+        writeln!(f, "players {},{}", self.players[0], self.players[1])?;
+        if let Some(clock) = &self.clock {
+            writeln!(f, "clock {clock}")?;
+        }
+        for step in &self.steps {
+            write!(f, "{} {} | {}", step.turn, step.action, step.revealed.join(","))?;
+            match step.elapsed_ms {
+                Some(elapsed_ms) => writeln!(f, " | {elapsed_ms}")?,
+                None => writeln!(f)?,
+            }
+        }
+        Ok(())
+    }
+}
+
+impl FromStr for Replay {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut lines = s.lines().map(|l| l.trim()).filter(|l| !l.is_empty());
+        match lines.next().and_then(|l| l.strip_prefix(REPLAY_HEADER)) {
+            Some(v) if v.trim().parse::<u32>() == Ok(REPLAY_VERSION) => {}
+            _ => return Err(format!("Not a version {REPLAY_VERSION} replay")),
+        }
+        let seed = lines.next()
+            .and_then(|l| l.strip_prefix("seed "))
+            .and_then(|v| v.trim().parse().ok())
+            .ok_or("Missing seed")?;
+        let players: Vec<String> = lines.next()
+            .and_then(|l| l.strip_prefix("players "))
+            .map(|v| v.split(',').map(|p| p.trim().to_string()).collect())
+            .ok_or("Missing players")?;
+        let players: [String; 2] = players.try_into().map_err(|_| "Expected two players")?;
+        let mut lines = lines.peekable();
+        let clock = match lines.peek().and_then(|l| l.strip_prefix("clock ")) {
+            Some(clock) => {
+                let clock = clock.parse()?;
+                lines.next();
+                Some(clock)
+            }
+            None => None,
+        };
+        let mut steps = vec![];
+        for line in lines {
+            let (step, rest) = line.split_once('|').ok_or(format!("Missing '|' in: {line}"))?;
+            let (turn, action) = step.trim().split_once(' ').ok_or(format!("Missing action in: {line}"))?;
+            let (revealed, elapsed_ms) = match rest.split_once('|') {
+                Some((revealed, ms)) => (revealed, Some(ms.trim().parse().map_err(|_| format!("Not a duration: {ms}"))?)),
+                None => (rest, None),
+            };
+            steps.push(ReplayStep {
+                turn: turn.parse().map_err(|_| format!("Not a turn: {turn}"))?,
+                action: action.parse()?,
+                revealed: revealed.split(',').map(|c| c.trim().to_string()).filter(|c| !c.is_empty()).collect(),
+                elapsed_ms,
+            });
+        }
+        Ok(Replay {
+            seed,
+            players,
+            clock,
+            steps,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::agents::{Agent, GreedyAgent};
+
+    #[test]
+    fn test_replay_roundtrip() {
+        let mut agent = GreedyAgent;
+        let mut record = Replay::new(11, ["greedy".to_string(), "greedy".to_string()]);
+        let mut gs = record.initial_state();
+        let mut states = vec![gs.clone()];
+        while gs.turn < 6 {
+            let action = agent.act(&gs);
+            gs = record.apply(&gs, action).unwrap();
+            states.push(gs.clone());
+        }
+        let parsed: Replay = record.to_string().parse().unwrap();
+        assert_eq!(parsed, record);
+        let rebuilt = replay(&parsed).unwrap();
+        assert_eq!(rebuilt.len(), states.len());
+        for (a, b) in rebuilt.iter().zip(states.iter()) {
+            assert_eq!(a.encode(0), b.encode(0));
+        }
+        assert_eq!(rebuilt[record.turn_start(3).unwrap()].turn, 3);
+        assert_eq!(replay(&record.truncated(4)).unwrap().len(), 5);
+    }
+
+    #[test]
+    fn test_replay_with_clock() {
+        let mut record = Replay::new(3, ["a".to_string(), "b".to_string()]);
+        record.clock = Some("10+2 end".parse().unwrap());
+        let gs = record.initial_state();
+        let gs = record.apply_timed(&gs, Action::Play(0), 4_000).unwrap();
+        assert!(record.apply(&gs, Action::Timeout).is_err());
+        let gs = record.apply_timed(&gs, Action::Timeout, 7_000).unwrap();
+        assert_eq!(gs.turn, 1);
+        assert_eq!(gs.clock.unwrap().remaining_ms, [2_000, 10_000]);
+
+        let parsed: Replay = record.to_string().parse().unwrap();
+        assert_eq!(parsed, record);
+        assert_eq!(replay(&parsed).unwrap().last().unwrap().clock, gs.clock);
+
+        record.clock = Some("10+0 forfeit".parse().unwrap());
+        let gs = record.initial_state();
+        let gs = record.apply_timed(&gs, Action::Timeout, 10_000).unwrap();
+        assert_eq!(gs.winner(), Some(1));
+    }
+
+    #[test]
+    fn test_replay_detects_divergence() {
+        let mut record = Replay::new(5, ["a".to_string(), "b".to_string()]);
+        let gs = record.initial_state();
+        record.apply(&gs, Action::EndTurn).unwrap();
+        record.steps[0].revealed = vec!["Cutter".to_string()];
+        assert!(replay(&record).is_err());
+    }
+}
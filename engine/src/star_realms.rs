@@ -0,0 +1,598 @@
+use std::fmt::{Display, Formatter};
+use rand::prelude::{SliceRandom};
+use rand::rngs::StdRng;
+use rand::{thread_rng, Rng, SeedableRng};
+use crate::abilities::Ability;
+use crate::cards::{Card, CardFactory, Faction};
+use crate::clock::Clock;
+use crate::error::RuleError;
+use crate::events::Event;
+use crate::gamelogic::Decision;
+use crate::zobrist::{counter_key, pending_key, player_key, zone_key, Counter};
+
+/// Number of card zones in the numeric encoding of a game state, see `GameState::encode`
+pub const ENCODED_ZONES: usize = 9;
+/// Number of scalar features appended after the zones in `GameState::encode`
+pub const ENCODED_SCALARS: usize = 15;
+
+/// A pile of cards of the game. Players are 0 and 1.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Zone {
+    Explorers,
+    TradeRow,
+    TradeDeck,
+    Scrap,
+    /// Cards that can still be played this turn
+    Hand(i32),
+    Deck(i32),
+    Discard(i32),
+    /// Ships played this turn and bases in play
+    Played(i32),
+}
+
+impl Zone {
+    pub const ALL: [Zone; 12] = [
+        Zone::Explorers, Zone::TradeRow, Zone::TradeDeck, Zone::Scrap,
+        Zone::Hand(0), Zone::Deck(0), Zone::Discard(0), Zone::Played(0),
+        Zone::Hand(1), Zone::Deck(1), Zone::Discard(1), Zone::Played(1),
+    ];
+
+    /// Whether the order of the cards counts: it does in decks, where it decides the next draws
+    pub fn is_ordered(self) -> bool {
+        matches!(self, Zone::TradeDeck | Zone::Deck(_))
+    }
+
+    /// Player whose cards the zone holds, `None` for the shared piles
+    pub fn owner(self) -> Option<i32> {
+        match self {
+            Zone::Hand(p) | Zone::Deck(p) | Zone::Discard(p) | Zone::Played(p) => Some(p),
+            Zone::Explorers | Zone::TradeRow | Zone::TradeDeck | Zone::Scrap => None,
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Zone::Explorers => "explorers",
+            Zone::TradeRow => "trade row",
+            Zone::TradeDeck => "trade deck",
+            Zone::Scrap => "scrap heap",
+            Zone::Hand(_) => "hand",
+            Zone::Deck(_) => "deck",
+            Zone::Discard(_) => "discard pile",
+            Zone::Played(_) => "played cards",
+        }
+    }
+
+    /// Whether the rules move `card` from this zone to `to`. Cards of a player stay theirs until
+    /// scrapped, acquired cards come from the trade row or the explorers, and scrapped explorers
+    /// go back to their pile instead of the scrap heap.
+    pub fn can_transfer(self, card: Card, to: Zone) -> bool {
+        let explorer = card == Card::Explorer;
+        match (self, to) {
+            (from, to) if from.owner().is_some() && to.owner().is_some() => from.owner() == to.owner(),
+            (Zone::Explorers, Zone::Deck(_) | Zone::Discard(_)) => explorer,
+            (Zone::TradeRow, Zone::Deck(_) | Zone::Discard(_) | Zone::Scrap) => !explorer,
+            (Zone::TradeDeck, Zone::TradeRow) => !explorer,
+            (Zone::Hand(_) | Zone::Discard(_) | Zone::Played(_), Zone::Scrap) => !explorer,
+            (Zone::Hand(_) | Zone::Discard(_) | Zone::Played(_), Zone::Explorers) => explorer,
+            _ => false,
+        }
+    }
+}
+
+/// Zones of players are numbered from 1, as in the game log
+impl Display for Zone {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self.owner() {
+            Some(p) => write!(f, "{} of player {}", self.name(), p + 1),
+            None => write!(f, "{}", self.name()),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct GameState {
+    pub explorers: Vec<Card>,
+    pub trade_row: Vec<Card>,
+    pub trade_deck: Vec<Card>,
+    pub scrap: Vec<Card>,
+    pub turn: i32,
+    pub players: (Player, Player),
+    pub pending: Vec<Decision>,
+    /// Time left to each player, for games played with a time control
+    pub clock: Option<Clock>,
+    /// What happened since the start of the current transition, see `GameLogic::step`
+    events: Vec<Event>,
+    rng: StdRng,
+    /// Kept up to date as the state changes, see `zobrist`
+    pub(crate) hash: u64,
+}
+
+impl GameState {
+    fn fill_trade_deck(&mut self) {
+        // Trade Federation
+        self.trade_deck.append(&mut CardFactory::n_of(3, Card::FederationShuttle));
+        self.trade_deck.append(&mut CardFactory::n_of(3, Card::Cutter));
+        self.trade_deck.append(&mut CardFactory::n_of(2, Card::EmbassyYacht));
+        self.trade_deck.append(&mut CardFactory::n_of(2, Card::Freighter));
+        self.trade_deck.append(&mut CardFactory::n_of(1, Card::CommandShip));
+        self.trade_deck.append(&mut CardFactory::n_of(1, Card::TradeEscort));
+        self.trade_deck.append(&mut CardFactory::n_of(1, Card::Flagship));
+        self.trade_deck.append(&mut CardFactory::n_of(2, Card::TradingPost));
+        self.trade_deck.append(&mut CardFactory::n_of(2, Card::BarterWorld));
+        self.trade_deck.append(&mut CardFactory::n_of(1, Card::DefenseCenter));
+        self.trade_deck.append(&mut CardFactory::n_of(1, Card::CentralOffice));
+        self.trade_deck.append(&mut CardFactory::n_of(1, Card::PortOfCall));
+
+        // Blob
+        self.trade_deck.append(&mut CardFactory::n_of(3, Card::BlobFighter));
+        self.trade_deck.append(&mut CardFactory::n_of(3, Card::TradePod));
+        self.trade_deck.append(&mut CardFactory::n_of(2, Card::BattlePod));
+        self.trade_deck.append(&mut CardFactory::n_of(2, Card::Ram));
+        self.trade_deck.append(&mut CardFactory::n_of(2, Card::BlobDestroyer));
+        self.trade_deck.append(&mut CardFactory::n_of(1, Card::BattleBlob));
+        self.trade_deck.append(&mut CardFactory::n_of(1, Card::BlobCarrier));
+        self.trade_deck.append(&mut CardFactory::n_of(1, Card::Mothership));
+        self.trade_deck.append(&mut CardFactory::n_of(3, Card::BlobWheel));
+        self.trade_deck.append(&mut CardFactory::n_of(1, Card::TheHive));
+        self.trade_deck.append(&mut CardFactory::n_of(1, Card::BlobWorld));
+
+        // Star Empire
+        self.trade_deck.append(&mut CardFactory::n_of(3, Card::ImperialFighter));
+        self.trade_deck.append(&mut CardFactory::n_of(3, Card::ImperialFrigate));
+        self.trade_deck.append(&mut CardFactory::n_of(3, Card::SurveyShip));
+        self.trade_deck.append(&mut CardFactory::n_of(2, Card::Corvette));
+        self.trade_deck.append(&mut CardFactory::n_of(1, Card::Battlecruiser));
+        self.trade_deck.append(&mut CardFactory::n_of(1, Card::Dreadnaught));
+        self.trade_deck.append(&mut CardFactory::n_of(2, Card::SpaceStation));
+        self.trade_deck.append(&mut CardFactory::n_of(2, Card::RecyclingStation));
+        self.trade_deck.append(&mut CardFactory::n_of(1, Card::WarWorld));
+        self.trade_deck.append(&mut CardFactory::n_of(1, Card::RoyalRedoubt));
+        self.trade_deck.append(&mut CardFactory::n_of(1, Card::FleetHq));
+
+        // Machine Cult
+        self.trade_deck.append(&mut CardFactory::n_of(3, Card::TradeBot));
+        self.trade_deck.append(&mut CardFactory::n_of(3, Card::MissileBot));
+        self.trade_deck.append(&mut CardFactory::n_of(3, Card::SupplyBot));
+        self.trade_deck.append(&mut CardFactory::n_of(2, Card::BattleStation));
+        self.trade_deck.append(&mut CardFactory::n_of(2, Card::PatrolMech));
+        self.trade_deck.append(&mut CardFactory::n_of(1, Card::StealthNeedle));
+        self.trade_deck.append(&mut CardFactory::n_of(1, Card::BattleMech));
+        self.trade_deck.append(&mut CardFactory::n_of(1, Card::MissileMech));
+        self.trade_deck.append(&mut CardFactory::n_of(1, Card::MechWorld));
+        self.trade_deck.append(&mut CardFactory::n_of(1, Card::BrainWorld));
+        self.trade_deck.append(&mut CardFactory::n_of(1, Card::MachineBase));
+        self.trade_deck.append(&mut CardFactory::n_of(1, Card::Junkyard));
+    }
+    fn mix_trade_deck(&mut self) {
+        self.trade_deck.shuffle(&mut self.rng);
+    }
+    fn from_trade_deck_to_row(&mut self) {
+        let poped = self.trade_deck.pop();
+        if let Some(c) = poped {
+            self.trade_row.push(c);
+        }
+    }
+
+    /// Fills the trade row slot `at` with the top card of the trade deck, if any
+    pub fn refill_trade_row(&mut self, at: usize) {
+        if let Some(&c) = self.trade_deck.last() {
+            self.hash ^= self.removal_key(Zone::TradeDeck, self.trade_deck.len() - 1) ^ self.push_key(Zone::TradeRow, c);
+            self.trade_deck.pop();
+            self.trade_row.insert(at.min(self.trade_row.len()), c);
+        }
+    }
+
+    pub fn new() -> GameState {
+        Self::with_seed(thread_rng().gen())
+    }
+
+    /// Same as `new`, but every shuffle of the game derives from `seed`
+    pub fn with_seed(seed: u64) -> GameState {
+        let mut rng = StdRng::seed_from_u64(seed);
+        let players = (Player::with_seed(rng.gen()), Player::with_seed(rng.gen()));
+        let mut gs = GameState {
+            explorers: vec![],
+            trade_row: vec![],
+            trade_deck: vec![],
+            scrap: vec![],
+            turn: 0,
+            players,
+            pending: vec![],
+            clock: None,
+            events: vec![],
+            rng,
+            hash: 0,
+        };
+        gs.explorers.append(&mut CardFactory::n_of(10, Card::Explorer));
+        gs.fill_trade_deck();
+        gs.mix_trade_deck();
+        for _ in 0..5 {
+            gs.from_trade_deck_to_row();
+        }
+        // First player starts with 3 cards, second one with a full hand
+        for _ in 0..3 {
+            gs.players.0.draw();
+        }
+        for _ in 0..5 {
+            gs.players.1.draw();
+        }
+        gs.rehash();
+        gs
+    }
+
+    pub fn mutate_players(&self, new_player: Player, player_id: i32) -> Result<GameState, RuleError> {
+        let (old, new_players) = match player_id {
+            0 => (&self.players.0, (new_player, self.players.1.clone())),
+            1 => (&self.players.1, (self.players.0.clone(), new_player)),
+            _ => return Err(RuleError::InvalidPlayer(player_id)),
+        };
+        let new = if player_id == 0 { &new_players.0 } else { &new_players.1 };
+        let hash = self.hash ^ player_key(player_id, old, new);
+        Ok(GameState {
+            explorers: self.explorers.clone(),
+            trade_row: self.trade_row.clone(),
+            trade_deck: self.trade_deck.clone(),
+            scrap: self.scrap.clone(),
+            turn: self.turn,
+            players: new_players,
+            pending: self.pending.clone(),
+            clock: self.clock,
+            events: self.events.clone(),
+            rng: self.rng.clone(),
+            hash,
+        })
+    }
+
+    /// Cards of `zone`, none for the zones of a player other than 0 and 1
+    pub fn zone(&self, zone: Zone) -> &[Card] {
+        match zone {
+            Zone::Explorers => &self.explorers,
+            Zone::TradeRow => &self.trade_row,
+            Zone::TradeDeck => &self.trade_deck,
+            Zone::Scrap => &self.scrap,
+            Zone::Hand(p) => self.get_player(p).map_or(&[], |p| &p.hand.playable),
+            Zone::Deck(p) => self.get_player(p).map_or(&[], |p| &p.deck),
+            Zone::Discard(p) => self.get_player(p).map_or(&[], |p| &p.discard),
+            Zone::Played(p) => self.get_player(p).map_or(&[], |p| &p.hand.played),
+        }
+    }
+
+    /// Player 0 or 1
+    pub fn get_player(&self, player_id: i32) -> Result<&Player, RuleError> {
+        match player_id {
+            0 => Ok(&self.players.0),
+            1 => Ok(&self.players.1),
+            _ => Err(RuleError::InvalidPlayer(player_id)),
+        }
+    }
+
+    /// Returns the id of the winning player once the other one has no authority left
+    pub fn winner(&self) -> Option<i32> {
+        if let Some(flagged) = self.clock.and_then(|c| c.flagged) {
+            Some(1 - flagged)
+        } else if self.players.1.authority <= 0 {
+            Some(0)
+        } else if self.players.0.authority <= 0 {
+            Some(1)
+        } else {
+            None
+        }
+    }
+
+    pub fn is_over(&self) -> bool {
+        self.winner().is_some()
+    }
+
+    /// The player whose turn it is
+    pub fn current_player(&self) -> &Player {
+        if self.turn % 2 == 0 { &self.players.0 } else { &self.players.1 }
+    }
+
+    /// The player waiting for their turn
+    pub fn opponent_player(&self) -> &Player {
+        if self.turn % 2 == 0 { &self.players.1 } else { &self.players.0 }
+    }
+
+    pub fn get_current_player(&self) -> Player {
+        if self.turn % 2 == 0 {
+            self.players.0.clone()
+        } else {
+            self.players.1.clone()
+        }
+    }
+
+    pub fn get_opponent_player(&self) -> Player {
+        if self.turn % 2 == 0 {
+            self.players.1.clone()
+        } else {
+            self.players.0.clone()
+        }
+    }
+
+    /// A copy where everything the current player cannot see is shuffled again from `seed`:
+    /// the trade deck, both decks and the opponent's hand, which is dealt from its deck and hand.
+    /// Future draws of the copy no longer follow the real game.
+    pub fn determinize(&self, seed: u64) -> GameState {
+        let mut gs = self.clone();
+        gs.rng = StdRng::seed_from_u64(seed);
+        gs.mix_trade_deck();
+        let opponent_id = (self.turn + 1) % 2;
+        for id in 0..2 {
+            let player = if id == 0 { &mut gs.players.0 } else { &mut gs.players.1 };
+            player.rng = StdRng::seed_from_u64(gs.rng.gen());
+            if id == opponent_id {
+                let in_hand = player.hand.playable.len();
+                player.deck.append(&mut player.hand.playable);
+                player.mix_deck();
+                for _ in 0..in_hand {
+                    player.draw();
+                }
+            } else {
+                player.mix_deck();
+            }
+        }
+        gs.rehash();
+        gs
+    }
+
+    fn player_mut(&mut self, player_id: i32) -> Result<&mut Player, RuleError> {
+        match player_id {
+            0 => Ok(&mut self.players.0),
+            1 => Ok(&mut self.players.1),
+            _ => Err(RuleError::InvalidPlayer(player_id)),
+        }
+    }
+
+    fn zone_mut(&mut self, zone: Zone) -> Result<&mut Vec<Card>, RuleError> {
+        Ok(match zone {
+            Zone::Explorers => &mut self.explorers,
+            Zone::TradeRow => &mut self.trade_row,
+            Zone::TradeDeck => &mut self.trade_deck,
+            Zone::Scrap => &mut self.scrap,
+            Zone::Hand(p) => &mut self.player_mut(p)?.hand.playable,
+            Zone::Deck(p) => &mut self.player_mut(p)?.deck,
+            Zone::Discard(p) => &mut self.player_mut(p)?.discard,
+            Zone::Played(p) => &mut self.player_mut(p)?.hand.played,
+        })
+    }
+
+    /// Moves the card at `at` in `from` on top of `to`, if the rules let it go there, see
+    /// `Zone::can_transfer`
+    pub fn transfer_at(&mut self, from: Zone, at: usize, to: Zone) -> Result<Card, RuleError> {
+        for owner in [from.owner(), to.owner()].into_iter().flatten() {
+            self.get_player(owner)?;
+        }
+        let card = *self.zone(from).get(at).ok_or(RuleError::CardNotInZone(from.name()))?;
+        if !from.can_transfer(card, to) {
+            return Err(RuleError::InvalidTransfer(card.get_name(), from, to));
+        }
+        self.hash ^= self.removal_key(from, at) ^ self.push_key(to, card);
+        self.zone_mut(from)?.remove(at);
+        if let Zone::Played(p) = from {
+            let ally_used = &mut self.player_mut(p)?.hand.ally_used;
+            if at < ally_used.len() {
+                ally_used.remove(at);
+            }
+        }
+        self.zone_mut(to)?.push(card);
+        Ok(card)
+    }
+
+    /// Moves the last `card` of `from` on top of `to`, see `transfer_at`
+    pub fn transfer(&mut self, card: Card, from: Zone, to: Zone) -> Result<(), RuleError> {
+        let at = self.zone(from).iter().rposition(|c| *c == card).ok_or(RuleError::CardNotInZone(from.name()))?;
+        self.transfer_at(from, at, to).map(|_| ())
+    }
+
+    /// Scraps the card at `at` in `from` for the current player: explorers go back to their pile,
+    /// other cards to the scrap heap
+    pub fn scrap_at(&mut self, from: Zone, at: usize) -> Result<Card, RuleError> {
+        let card = *self.zone(from).get(at).ok_or(RuleError::CardNotInZone(from.name()))?;
+        let to = if card == Card::Explorer { Zone::Explorers } else { Zone::Scrap };
+        self.transfer_at(from, at, to)?;
+        self.emit(Event::CardScrapped { player: self.turn % 2, card });
+        Ok(card)
+    }
+
+    /// Scraps the last `card` of `from`, see `scrap_at`
+    pub fn scrap(&mut self, card: Card, from: Zone) -> Result<(), RuleError> {
+        let at = self.zone(from).iter().rposition(|c| *c == card).ok_or(RuleError::CardNotInZone(from.name()))?;
+        self.scrap_at(from, at).map(|_| ())
+    }
+
+    /// `player_id` draws `n` cards, shuffling their discard pile when the deck runs out
+    pub fn draw(&mut self, player_id: i32, n: usize) -> Result<(), RuleError> {
+        self.get_player(player_id)?;
+        let mut events = vec![];
+        let (deck, discard) = (Zone::Deck(player_id), Zone::Discard(player_id));
+        let before = self.zone(Zone::Hand(player_id)).len();
+        for _ in 0..n {
+            if self.zone(deck).is_empty() && !self.zone(discard).is_empty() {
+                events.push(Event::Shuffled { player: player_id });
+                self.hash ^= zone_key(discard, self.zone(discard));
+                self.player_mut(player_id)?.deck_from_discard();
+                self.hash ^= zone_key(deck, self.zone(deck));
+            }
+            let top = self.zone(deck).len().checked_sub(1);
+            if top.and_then(|at| self.transfer_at(deck, at, Zone::Hand(player_id)).ok()).is_none() {
+                break;
+            }
+        }
+        let count = self.zone(Zone::Hand(player_id)).len() - before;
+        if count > 0 {
+            events.push(Event::CardsDrawn { player: player_id, count });
+        }
+        self.events.append(&mut events);
+        Ok(())
+    }
+
+    /// Changes the pending decisions with `f`, keeping the hash up to date
+    pub(crate) fn update_pending<T>(&mut self, f: impl FnOnce(&mut Vec<Decision>) -> T) -> T {
+        self.hash ^= pending_key(&self.pending);
+        let result = f(&mut self.pending);
+        self.hash ^= pending_key(&self.pending);
+        result
+    }
+
+    pub(crate) fn next_turn(&mut self) {
+        self.hash ^= counter_key(Counter::Turn, self.turn) ^ counter_key(Counter::Turn, self.turn + 1);
+        self.turn += 1;
+    }
+
+    pub(crate) fn emit(&mut self, event: Event) {
+        self.events.push(event);
+    }
+
+    pub(crate) fn take_events(&mut self) -> Vec<Event> {
+        std::mem::take(&mut self.events)
+    }
+
+    /// Size of the vector returned by `encode`
+    pub fn encoded_len() -> usize {
+        ENCODED_ZONES * CardFactory::catalog().len() + ENCODED_SCALARS
+    }
+
+    /// Numeric encoding of what `player_id` knows about the game.
+    ///
+    /// For each zone, in order: own hand, own deck, own discard, own played cards,
+    /// opponent's unseen cards (deck and hand), opponent's discard, opponent's played cards,
+    /// trade row and scrap heap, it gives the number of cards of each kind of
+    /// `CardFactory::catalog`. Then comes own authority, opponent authority, trade, damages,
+    /// turn, explorers left and pending decisions, and from the `analysis` of the player: the
+    /// expected trade, damages and authority of their next hand, the hands left before a
+    /// reshuffle and the probabilities of Blob, Machine Cult, Star Empire and Trade Federation
+    /// allies in it.
+    pub fn encode(&self, player_id: i32) -> Result<Vec<f32>, RuleError> {
+        let me = self.get_player(player_id)?;
+        let opponent = self.get_player(1 - player_id)?;
+        let opponent_unseen: Vec<Card> = opponent.deck.iter().chain(opponent.hand.playable.iter()).cloned().collect();
+        let zones: [&Vec<Card>; ENCODED_ZONES] = [
+            &me.hand.playable,
+            &me.deck,
+            &me.discard,
+            &me.hand.played,
+            &opponent_unseen,
+            &opponent.discard,
+            &opponent.hand.played,
+            &self.trade_row,
+            &self.scrap,
+        ];
+        let mut features = Vec::with_capacity(Self::encoded_len());
+        for zone in zones {
+            let mut counts = vec![0.; Card::ALL.len()];
+            for card in zone {
+                counts[card.index()] += 1.;
+            }
+            features.append(&mut counts);
+        }
+        features.push(me.authority as f32);
+        features.push(opponent.authority as f32);
+        features.push(me.hand.trade as f32);
+        features.push(me.hand.damage as f32);
+        features.push(self.turn as f32);
+        features.push(self.explorers.len() as f32);
+        features.push(self.pending.len() as f32);
+        let analysis = me.analysis();
+        features.push(analysis.expected_trade() as f32);
+        features.push(analysis.expected_damage() as f32);
+        features.push(analysis.expected_authority() as f32);
+        features.push(analysis.turns_until_reshuffle() as f32);
+        for faction in [Faction::Blob, Faction::Machine, Faction::Star, Faction::Trade] {
+            features.push(analysis.ally_probability(&faction) as f32);
+        }
+        Ok(features)
+    }
+}
+#[derive(Debug, Clone)]
+pub struct Hand {
+    pub played: Vec<Card>,
+    pub playable: Vec<Card>,
+    /// Delayed abilities, which live in the card definitions
+    pub abilities: Vec<&'static Ability>,
+    pub trade: i32,
+    pub damage: i32,
+    pub next_n_ships_on_top: i32,
+    pub next_n_ships_free: i32,
+    pub ally_used: Vec<bool>,
+    /// Damages added by each ship played for the rest of the turn
+    pub damage_per_ship: i32,
+    /// Ship copied this turn by the Stealth Needle in play
+    pub copied: Option<Card>,
+    /// Cards to discard at the start of the next turn, asked by the opponent
+    pub discards: i32,
+}
+
+impl Hand {
+    pub fn new() -> Hand {
+        Hand {
+            played: vec![],
+            playable: vec![],
+            abilities: vec![],
+            trade: 0,
+            damage: 0,
+            next_n_ships_on_top: 0,
+            next_n_ships_free: 0,
+            ally_used: vec![],
+            damage_per_ship: 0,
+            copied: None,
+            discards: 0,
+        }
+    }
+
+    pub fn get_played_bases(&self) -> Vec<&Card> {
+        self.played.iter().filter(|c| c.is_base()).collect()
+    }
+
+    pub fn get_played_ships(&self) -> Vec<&Card> {
+        self.played.iter().filter(|c| !c.is_base()).collect()
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Player {
+    pub discard: Vec<Card>,
+    pub deck: Vec<Card>,
+    pub hand: Hand,
+    pub authority: i32,
+    rng: StdRng,
+}
+
+impl Player {
+    pub fn new() -> Player {
+        Self::with_seed(thread_rng().gen())
+    }
+
+    pub fn with_seed(seed: u64) -> Player {
+        let mut p = Player {
+            discard: vec![],
+            deck: vec![],
+            hand: Hand::new(),
+            authority: 50,
+            rng: StdRng::seed_from_u64(seed),
+        };
+        p.deck.append(&mut CardFactory::n_of(8, Card::Scout));
+        p.deck.append(&mut CardFactory::n_of(2, Card::Viper));
+        p.mix_deck();
+        p
+    }
+
+    pub fn deck_from_discard(&mut self) {
+        self.deck = self.discard.clone();
+        self.discard.clear();
+        self.mix_deck();
+    }
+
+    fn mix_deck(&mut self) {
+        self.deck.shuffle(&mut self.rng);
+    }
+
+    pub fn draw(&mut self) {
+        if self.deck.len() == 0 {
+            self.deck_from_discard();
+        }
+        if let Some(card) = self.deck.pop() {
+            self.hand.playable.push(card);
+        }
+    }
+}
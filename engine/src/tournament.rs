@@ -0,0 +1,320 @@
+use std::fmt::{Display, Formatter};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Mutex;
+use std::thread;
+use crate::agents::Agent;
+use crate::events::Event;
+use crate::gamelogic::GameLogic;
+use crate::star_realms::GameState;
+
+/// Builds a fresh agent for one game from a seed
+pub type AgentFactory = Box<dyn Fn(u64) -> Box<dyn Agent> + Send + Sync>;
+
+pub struct Entrant {
+    pub name: String,
+    pub factory: AgentFactory,
+}
+
+impl Entrant {
+    pub fn new(name: &str, factory: AgentFactory) -> Entrant {
+        Entrant {
+            name: name.to_string(),
+            factory,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct TournamentConfig {
+    /// Games played by each pair of entrants
+    pub games: usize,
+    pub seed: u64,
+    /// Worker threads, all the available cores when 0
+    pub threads: usize,
+    /// Games still running after this many turns are draws
+    pub max_turns: i32,
+}
+
+impl Default for TournamentConfig {
+    fn default() -> Self {
+        TournamentConfig {
+            games: 100,
+            seed: 0,
+            threads: 0,
+            max_turns: 200,
+        }
+    }
+}
+
+/// Outcome of one game, entrants are given by index
+#[derive(Debug, Clone)]
+pub struct GameResult {
+    pub first: usize,
+    pub second: usize,
+    pub winner: Option<usize>,
+    pub turns: i32,
+}
+
+/// Plays one game, `agents.0` moving first. `observer` is given the state before each action
+/// and the events of that action.
+pub fn play_game(agents: (&mut dyn Agent, &mut dyn Agent), seed: u64, max_turns: i32,
+                 mut observer: impl FnMut(&GameState, &[Event])) -> (Option<i32>, i32) {
+    let (first, second) = agents;
+    let mut gs = GameState::with_seed(seed);
+    while !gs.is_over() && gs.turn < max_turns {
+        let action = if gs.turn % 2 == 0 { first.act(&gs) } else { second.act(&gs) };
+        gs = match GameLogic::step(&gs, &action) {
+            Ok((next, events)) => {
+                observer(&gs, &events);
+                next
+            }
+            // An agent unable to follow the rules loses the game
+            Err(_) => return (Some((gs.turn + 1) % 2), gs.turn),
+        };
+    }
+    (gs.winner(), gs.turn)
+}
+
+pub struct Tournament {
+    pub entrants: Vec<Entrant>,
+    pub config: TournamentConfig,
+}
+
+impl Tournament {
+    pub fn new(entrants: Vec<Entrant>, config: TournamentConfig) -> Tournament {
+        Tournament {
+            entrants,
+            config,
+        }
+    }
+
+    /// Every game to play as (first, second, seed). First player alternates within a pair, both
+    /// seatings replaying the same deal so that it does not add to the difference.
+    fn schedule(&self) -> Vec<(usize, usize, u64)> {
+        let mut jobs = vec![];
+        for a in 0..self.entrants.len() {
+            for b in a + 1..self.entrants.len() {
+                for g in 0..self.config.games {
+                    let deal = jobs.len() - g % 2;
+                    let seed = self.config.seed
+                        .wrapping_mul(6364136223846793005)
+                        .wrapping_add((deal as u64).wrapping_mul(1442695040888963407));
+                    let (first, second) = if g % 2 == 0 { (a, b) } else { (b, a) };
+                    jobs.push((first, second, seed));
+                }
+            }
+        }
+        jobs
+    }
+
+    pub fn run(&self) -> TournamentReport {
+        let jobs = self.schedule();
+        let games = run_parallel(&jobs, self.config.threads, |&(first, second, seed)| {
+            let mut a = (self.entrants[first].factory)(seed);
+            let mut b = (self.entrants[second].factory)(seed.wrapping_add(1));
+            let (winner, turns) = play_game((a.as_mut(), b.as_mut()), seed, self.config.max_turns, |_, _| {});
+            let winner = winner.map(|w| if w == 0 { first } else { second });
+            GameResult { first, second, winner, turns }
+        });
+        TournamentReport::new(self.entrants.iter().map(|e| e.name.clone()).collect(), games)
+    }
+}
+
+/// Runs `f` on every job over `threads` worker threads, all the available cores when 0, and
+/// gives the results in the order of the jobs
+pub fn run_parallel<J: Sync, T: Send>(jobs: &[J], threads: usize, f: impl Fn(&J) -> T + Sync) -> Vec<T> {
+    let threads = match threads {
+        0 => thread::available_parallelism().map_or(1, |n| n.get()),
+        n => n,
+    };
+    let next = AtomicUsize::new(0);
+    let results: Mutex<Vec<Option<T>>> = Mutex::new(jobs.iter().map(|_| None).collect());
+    thread::scope(|s| {
+        for _ in 0..threads.min(jobs.len().max(1)) {
+            s.spawn(|| loop {
+                let i = next.fetch_add(1, Ordering::Relaxed);
+                let Some(job) = jobs.get(i) else { break };
+                let result = f(job);
+                results.lock().unwrap()[i] = Some(result);
+            });
+        }
+    });
+    results.into_inner().unwrap().into_iter().flatten().collect()
+}
+
+/// Wilson score interval at 95% for `wins` successes out of `n`
+pub fn wilson_interval(wins: f64, n: f64) -> (f64, f64) {
+    if n == 0. {
+        return (0., 1.);
+    }
+    let z = 1.96;
+    let p = wins / n;
+    let denominator = 1. + z * z / n;
+    let center = (p + z * z / (2. * n)) / denominator;
+    let margin = z * ((p * (1. - p) + z * z / (4. * n)) / n).sqrt() / denominator;
+    ((center - margin).max(0.), (center + margin).min(1.))
+}
+
+#[derive(Debug, Clone)]
+pub struct EntrantStats {
+    pub name: String,
+    pub games: usize,
+    pub wins: usize,
+    pub draws: usize,
+    pub elo: f64,
+}
+
+impl EntrantStats {
+    /// Win rate counting draws as half a win
+    pub fn win_rate(&self) -> f64 {
+        if self.games == 0 {
+            return 0.;
+        }
+        (self.wins as f64 + 0.5 * self.draws as f64) / self.games as f64
+    }
+
+    pub fn confidence_interval(&self) -> (f64, f64) {
+        wilson_interval(self.wins as f64 + 0.5 * self.draws as f64, self.games as f64)
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct TournamentReport {
+    pub games: Vec<GameResult>,
+    pub entrants: Vec<EntrantStats>,
+}
+
+impl TournamentReport {
+    pub fn new(names: Vec<String>, games: Vec<GameResult>) -> TournamentReport {
+        let elos = Self::fit_elo(names.len(), &games);
+        let entrants = names.into_iter().enumerate().map(|(i, name)| {
+            let played = games.iter().filter(|g| g.first == i || g.second == i);
+            let (mut n, mut wins, mut draws) = (0, 0, 0);
+            for g in played {
+                n += 1;
+                match g.winner {
+                    Some(w) if w == i => wins += 1,
+                    None => draws += 1,
+                    _ => {}
+                }
+            }
+            EntrantStats { name, games: n, wins, draws, elo: elos[i] }
+        }).collect();
+        TournamentReport {
+            games,
+            entrants,
+        }
+    }
+
+    /// Bradley-Terry ratings fitted with the MM algorithm, on the Elo scale around 1500
+    fn fit_elo(n: usize, games: &[GameResult]) -> Vec<f64> {
+        let mut wins = vec![vec![0.; n]; n];
+        for g in games {
+            match g.winner {
+                Some(w) => {
+                    let l = if w == g.first { g.second } else { g.first };
+                    wins[w][l] += 1.;
+                }
+                None => {
+                    wins[g.first][g.second] += 0.5;
+                    wins[g.second][g.first] += 0.5;
+                }
+            }
+        }
+        // A virtual draw against each other entrant keeps ratings finite for unbeaten agents
+        for (i, row) in wins.iter_mut().enumerate() {
+            for (j, w) in row.iter_mut().enumerate() {
+                if i != j {
+                    *w += 0.5;
+                }
+            }
+        }
+        let mut strength = vec![1.; n];
+        for _ in 0..200 {
+            let mut next = vec![0.; n];
+            for i in 0..n {
+                let total_wins: f64 = wins[i].iter().sum();
+                let denominator: f64 = (0..n)
+                    .filter(|&j| j != i)
+                    .map(|j| (wins[i][j] + wins[j][i]) / (strength[i] + strength[j]))
+                    .sum();
+                next[i] = if denominator > 0. { total_wins / denominator } else { strength[i] };
+            }
+            let mean_log = next.iter().map(|s: &f64| s.ln()).sum::<f64>() / n as f64;
+            strength = next.iter().map(|s| (s.ln() - mean_log).exp()).collect();
+        }
+        strength.iter().map(|s| 1500. + 400. * s.log10()).collect()
+    }
+
+    pub fn average_length(&self) -> f64 {
+        if self.games.is_empty() {
+            return 0.;
+        }
+        self.games.iter().map(|g| g.turns as f64).sum::<f64>() / self.games.len() as f64
+    }
+
+    /// Share of the decided games won by the first player, with its 95% interval
+    pub fn first_player_advantage(&self) -> (f64, (f64, f64)) {
+        let decided: Vec<&GameResult> = self.games.iter().filter(|g| g.winner.is_some()).collect();
+        let first_wins = decided.iter().filter(|g| g.winner == Some(g.first)).count() as f64;
+        let n = decided.len() as f64;
+        let rate = if n > 0. { first_wins / n } else { 0. };
+        (rate, wilson_interval(first_wins, n))
+    }
+}
+
+impl Display for TournamentReport {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        writeln!(f, "{:<16} {:>6} {:>6} {:>6} {:>8} {:>17} {:>7}", "agent", "games", "wins", "draws", "win %", "95% CI", "elo")?;
+        let mut ranked = self.entrants.clone();
+        ranked.sort_by(|a, b| b.elo.total_cmp(&a.elo));
+        for e in ranked {
+            let (low, high) = e.confidence_interval();
+            writeln!(f, "{:<16} {:>6} {:>6} {:>6} {:>7.1}% {:>7.1}% - {:>5.1}% {:>7.0}",
+                     e.name, e.games, e.wins, e.draws, e.win_rate() * 100., low * 100., high * 100., e.elo)?;
+        }
+        let (rate, (low, high)) = self.first_player_advantage();
+        writeln!(f, "average game length: {:.1} turns", self.average_length())?;
+        write!(f, "first player wins: {:.1}% ({:.1}% - {:.1}%)", rate * 100., low * 100., high * 100.)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::agents::{GreedyAgent, RandomAgent};
+
+    #[test]
+    fn test_tournament() {
+        let entrants = vec![
+            Entrant::new("greedy", Box::new(|_| Box::new(GreedyAgent))),
+            Entrant::new("random", Box::new(|seed| Box::new(RandomAgent::new(seed)))),
+        ];
+        let config = TournamentConfig { games: 6, seed: 1, threads: 2, max_turns: 100 };
+        let report = Tournament::new(entrants, config).run();
+        assert_eq!(report.games.len(), 6);
+        assert_eq!(report.games.iter().filter(|g| g.first == 0).count(), 3);
+        let greedy = &report.entrants[0];
+        assert!(greedy.wins >= 5);
+        assert!(greedy.elo > report.entrants[1].elo);
+    }
+
+    #[test]
+    fn test_seatings_share_a_deal() {
+        let entrants = ["a", "b", "c"].iter().map(|n| Entrant::new(n, Box::new(|_| Box::new(GreedyAgent)))).collect();
+        let config = TournamentConfig { games: 4, ..TournamentConfig::default() };
+        let jobs = Tournament::new(entrants, config).schedule();
+        assert_eq!(jobs.len(), 12);
+        for pair in jobs.chunks(2) {
+            assert_eq!((pair[0].0, pair[0].1, pair[0].2), (pair[1].1, pair[1].0, pair[1].2));
+        }
+        assert_ne!(jobs[0].2, jobs[2].2);
+    }
+
+    #[test]
+    fn test_wilson_interval() {
+        let (low, high) = wilson_interval(50., 100.);
+        assert!(low < 0.5 && high > 0.5);
+        assert!((high - 0.5 - (0.5 - low)).abs() < 1e-9);
+    }
+}
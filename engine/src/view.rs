@@ -0,0 +1,170 @@
+use crate::abilities::{Ability, AtomicAbilityFn};
+use crate::cards::{Card, CardFactory};
+use crate::clock::Clock;
+use crate::gamelogic::{Action, GameLogic};
+use crate::star_realms::{GameState, Player};
+
+/// What can be seen of a player. Cards are given by name.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct PlayerView {
+    pub authority: i32,
+    pub trade: i32,
+    pub damage: i32,
+    /// Size of the deck, its order is never shown
+    pub deck: usize,
+    pub discard: Vec<String>,
+    pub played: Vec<String>,
+    /// Missing when the viewer may not look at it
+    pub hand: Option<Vec<String>>,
+    pub hand_size: usize,
+    /// Texts of the delayed abilities waiting to be used
+    pub abilities: Vec<String>,
+}
+
+/// The decision the current player has to take
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum PendingView {
+    /// One of the two sides of an `Ability::Or`, or neither with `Action::Decline`
+    Choice { branches: [String; 2] },
+    /// Between `min` and `max` cards picked with `Action::Select`, the decision can only be
+    /// declined with `min` at 0 or no card left to select.
+    /// A `single` card ability applies on selection, the others wait for `Action::Confirm`.
+    Cards { prompt: String, min: usize, max: usize, single: bool },
+}
+
+impl PendingView {
+    /// The first pending decision of `gs`
+    pub fn of(gs: &GameState) -> Option<PendingView> {
+        match &gs.pending.first()?.ability {
+            Ability::Or(a, b) => Some(PendingView::Choice { branches: [a.to_string(), b.to_string()] }),
+            Ability::Atomic(a) => {
+                let single = matches!(a.ability(), AtomicAbilityFn::Card(_));
+                Some(PendingView::Cards {
+                    prompt: a.to_string(),
+                    min: a.min_choices(),
+                    max: a.max_choices(),
+                    single,
+                })
+            }
+            _ => None,
+        }
+    }
+}
+
+/// A game as seen from one seat, or by a spectator who sees no hand
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct TableView {
+    pub seat: Option<i32>,
+    pub turn: i32,
+    pub players: [PlayerView; 2],
+    pub trade_row: Vec<String>,
+    pub trade_deck: usize,
+    pub explorers: usize,
+    pub scrap: Vec<String>,
+    pub pending: Option<PendingView>,
+    /// Empty unless the viewer is the current player
+    pub legal_actions: Vec<Action>,
+    pub winner: Option<i32>,
+    /// Time left to each player when the view was taken, the current player's clock is running
+    pub clock: Option<Clock>,
+}
+
+fn names(cards: &[Card]) -> Vec<String> {
+    cards.iter().map(|c| c.get_name()).collect()
+}
+
+fn cards(names: &[String]) -> Result<Vec<Card>, String> {
+    names.iter().map(|n| CardFactory::by_name(n).ok_or(format!("Unknown card: {n}"))).collect()
+}
+
+impl PlayerView {
+    fn of(player: &Player, hand_visible: bool) -> PlayerView {
+        PlayerView {
+            authority: player.authority,
+            trade: player.hand.trade,
+            damage: player.hand.damage,
+            deck: player.deck.len(),
+            discard: names(&player.discard),
+            played: names(&player.hand.played),
+            hand: hand_visible.then(|| names(&player.hand.playable)),
+            hand_size: player.hand.playable.len(),
+            abilities: player.hand.abilities.iter().map(|a| a.to_string()).collect(),
+        }
+    }
+}
+
+impl TableView {
+    pub fn of(gs: &GameState, seat: Option<i32>) -> TableView {
+        let current = gs.turn % 2;
+        TableView {
+            seat,
+            turn: gs.turn,
+            players: [
+                PlayerView::of(&gs.players.0, seat == Some(0)),
+                PlayerView::of(&gs.players.1, seat == Some(1)),
+            ],
+            trade_row: names(&gs.trade_row),
+            trade_deck: gs.trade_deck.len(),
+            explorers: gs.explorers.len(),
+            scrap: names(&gs.scrap),
+            pending: PendingView::of(gs),
+            legal_actions: if seat == Some(current) && !gs.is_over() { GameLogic::legal_actions(gs) } else { vec![] },
+            winner: gs.winner(),
+            clock: gs.clock,
+        }
+    }
+
+    /// A state that looks like this view, to draw it.
+    /// Cards the viewer cannot see are scouts, pending decisions and delayed abilities are left out:
+    /// they are only in `pending` and `abilities`.
+    pub fn to_state(&self) -> Result<GameState, String> {
+        let mut gs = GameState::with_seed(0);
+        gs.turn = self.turn;
+        gs.trade_row = cards(&self.trade_row)?;
+        gs.trade_deck = CardFactory::n_of(self.trade_deck, Card::Scout);
+        gs.explorers = CardFactory::n_of(self.explorers, Card::Explorer);
+        gs.scrap = cards(&self.scrap)?;
+        gs.pending = vec![];
+        gs.clock = self.clock;
+        for (player, view) in [&mut gs.players.0, &mut gs.players.1].into_iter().zip(self.players.iter()) {
+            player.authority = view.authority;
+            player.hand.trade = view.trade;
+            player.hand.damage = view.damage;
+            player.deck = CardFactory::n_of(view.deck, Card::Scout);
+            player.discard = cards(&view.discard)?;
+            player.hand.played = cards(&view.played)?;
+            player.hand.playable = match &view.hand {
+                Some(hand) => cards(hand)?,
+                None => CardFactory::n_of(view.hand_size, Card::Scout),
+            };
+            player.hand.abilities = vec![];
+        }
+        gs.rehash();
+        Ok(gs)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_view_hides_hands() {
+        let gs = GameState::with_seed(2);
+        let view = TableView::of(&gs, Some(0));
+        assert!(view.players[0].hand.is_some());
+        assert_eq!(view.players[1].hand, None);
+        assert_eq!(view.players[1].hand_size, 5);
+        assert_eq!(view.legal_actions, GameLogic::legal_actions(&gs));
+        assert!(TableView::of(&gs, Some(1)).legal_actions.is_empty());
+        assert!(TableView::of(&gs, None).players.iter().all(|p| p.hand.is_none()));
+
+        let drawn = view.to_state().unwrap();
+        assert_eq!(names(&drawn.get_player(0).unwrap().hand.playable), names(&gs.get_player(0).unwrap().hand.playable));
+        assert_eq!(drawn.get_player(1).unwrap().hand.playable.len(), 5);
+        assert_eq!(names(&drawn.trade_row), names(&gs.trade_row));
+    }
+}
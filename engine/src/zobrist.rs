@@ -0,0 +1,378 @@
+use std::hash::{Hash, Hasher};
+use crate::abilities::Ability;
+use crate::cards::Card;
+use crate::gamelogic::Decision;
+use crate::star_realms::{GameState, Player, Zone};
+
+/// Numbers of a game state beside its cards. Players are 0 and 1.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Counter {
+    Turn,
+    Authority(i32),
+    Trade(i32),
+    Damage(i32),
+    ShipsOnTop(i32),
+    ShipsFree(i32),
+    DamagePerShip(i32),
+    /// Index in `Card::ALL` of the ship copied by a Stealth Needle, -1 for none
+    Copied(i32),
+    Discards(i32),
+}
+
+impl Counter {
+    pub const ALL: [Counter; 17] = [
+        Counter::Turn,
+        Counter::Authority(0), Counter::Trade(0), Counter::Damage(0), Counter::ShipsOnTop(0), Counter::ShipsFree(0),
+        Counter::DamagePerShip(0), Counter::Copied(0), Counter::Discards(0),
+        Counter::Authority(1), Counter::Trade(1), Counter::Damage(1), Counter::ShipsOnTop(1), Counter::ShipsFree(1),
+        Counter::DamagePerShip(1), Counter::Copied(1), Counter::Discards(1),
+    ];
+
+    /// The player the counter belongs to, `None` for the turn
+    pub fn player(self) -> Option<i32> {
+        match self {
+            Counter::Turn => None,
+            Counter::Authority(p) | Counter::Trade(p) | Counter::Damage(p) | Counter::ShipsOnTop(p)
+            | Counter::ShipsFree(p) | Counter::DamagePerShip(p) | Counter::Copied(p) | Counter::Discards(p) => Some(p),
+        }
+    }
+
+    pub fn value(self, gs: &GameState) -> i32 {
+        match self.player() {
+            Some(p) => gs.get_player(p).map_or(0, |player| self.value_of(player)),
+            None => gs.turn,
+        }
+    }
+
+    /// Value of the counter for `player`, whichever player it belongs to. 0 for the turn.
+    fn value_of(self, player: &Player) -> i32 {
+        match self {
+            Counter::Turn => 0,
+            Counter::Authority(_) => player.authority,
+            Counter::Trade(_) => player.hand.trade,
+            Counter::Damage(_) => player.hand.damage,
+            Counter::ShipsOnTop(_) => player.hand.next_n_ships_on_top,
+            Counter::ShipsFree(_) => player.hand.next_n_ships_free,
+            Counter::DamagePerShip(_) => player.hand.damage_per_ship,
+            Counter::Copied(_) => player.hand.copied.map_or(-1, |c| c.index() as i32),
+            Counter::Discards(_) => player.hand.discards,
+        }
+    }
+}
+
+// Kinds of keys, mixed into each key so that two parts of a state never share one
+const CARD: u64 = 1;
+const COUNTER: u64 = 2;
+const ALLY: u64 = 3;
+const ABILITY: u64 = 4;
+const PENDING: u64 = 5;
+
+/// The splitmix64 finalizer: a cheap stand-in for a table of random numbers
+fn mix(x: u64) -> u64 {
+    let mut z = x.wrapping_add(0x9E37_79B9_7F4A_7C15);
+    z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+    z ^ (z >> 31)
+}
+
+fn key(kind: u64, parts: &[u64]) -> u64 {
+    parts.iter().fold(mix(kind), |h, p| mix(h ^ p))
+}
+
+/// FNV-1a, to key effects, conditions and targets without formatting them
+struct Fnv(u64);
+
+impl Hasher for Fnv {
+    fn finish(&self) -> u64 {
+        self.0
+    }
+
+    fn write(&mut self, bytes: &[u8]) {
+        for b in bytes {
+            self.0 = (self.0 ^ *b as u64).wrapping_mul(0x100_0000_01B3);
+        }
+    }
+}
+
+fn fnv<T: Hash + ?Sized>(value: &T) -> u64 {
+    let mut hasher = Fnv(0xCBF2_9CE4_8422_2325);
+    value.hash(&mut hasher);
+    hasher.finish()
+}
+
+fn zone_id(zone: Zone) -> u64 {
+    Zone::ALL.iter().position(|z| *z == zone).unwrap_or(0) as u64
+}
+
+/// Key of `card` at `slot` of `zone`. The slot is the position in ordered zones and the number
+/// of copies of the card already there in the others, where cards of the same kind are
+/// interchangeable.
+pub fn card_key(zone: Zone, card: Card, slot: usize) -> u64 {
+    key(CARD, &[zone_id(zone), card.index() as u64, slot as u64])
+}
+
+pub fn counter_key(counter: Counter, value: i32) -> u64 {
+    let id = Counter::ALL.iter().position(|c| *c == counter).unwrap_or(0) as u64;
+    key(COUNTER, &[id, value as u64])
+}
+
+fn ally_key(player_id: i32, card: Card, slot: usize) -> u64 {
+    key(ALLY, &[player_id as u64, card.index() as u64, slot as u64])
+}
+
+/// Key of an ability, from its effects and conditions since abilities are closures
+fn ability_key(ability: &Ability) -> u64 {
+    match ability {
+        Ability::Atomic(a) => key(ABILITY, &[0, fnv(a.effect())]),
+        Ability::And(a, b) => key(ABILITY, &[1, ability_key(a), ability_key(b)]),
+        Ability::Or(a, b) => key(ABILITY, &[2, ability_key(a), ability_key(b)]),
+        Ability::Cond(pred, a) => key(ABILITY, &[3, fnv(pred.description()), ability_key(a)]),
+        Ability::Delayed(a) => key(ABILITY, &[4, ability_key(a)]),
+    }
+}
+
+/// Whether two abilities have the same effects and conditions, see `ability_key`
+fn same_ability(a: &Ability, b: &Ability) -> bool {
+    match (a, b) {
+        (Ability::Atomic(a), Ability::Atomic(b)) => a.effect() == b.effect(),
+        (Ability::And(a1, b1), Ability::And(a2, b2)) | (Ability::Or(a1, b1), Ability::Or(a2, b2)) => {
+            same_ability(a1, a2) && same_ability(b1, b2)
+        }
+        (Ability::Cond(p1, a1), Ability::Cond(p2, a2)) => {
+            p1.description() == p2.description() && same_ability(a1, a2)
+        }
+        (Ability::Delayed(a1), Ability::Delayed(a2)) => same_ability(a1, a2),
+        _ => false,
+    }
+}
+
+fn copies(abilities: &[&Ability], ability: &Ability) -> usize {
+    abilities.iter().filter(|a| same_ability(a, ability)).count()
+}
+
+/// Delayed abilities of player `player_id`, which can be used in any order
+fn abilities_key(player_id: i32, abilities: &[&Ability]) -> u64 {
+    abilities.iter().enumerate().fold(0, |h, (i, a)| {
+        h ^ key(ABILITY, &[16 + player_id as u64, ability_key(a), copies(&abilities[..i], a) as u64])
+    })
+}
+
+pub(crate) fn pending_key(pending: &[Decision]) -> u64 {
+    pending.iter().enumerate()
+        .fold(0, |h, (i, d)| h ^ key(PENDING, &[i as u64, ability_key(d.ability), fnv(&d.selected)]))
+}
+
+fn counts(cards: impl IntoIterator<Item = Card>) -> [u16; Card::ALL.len()] {
+    let mut counts = [0; Card::ALL.len()];
+    for card in cards {
+        counts[card.index()] += 1;
+    }
+    counts
+}
+
+fn multiset_key(cards: impl IntoIterator<Item = Card>, card_key: impl Fn(Card, usize) -> u64) -> u64 {
+    let mut copies = [0; Card::ALL.len()];
+    cards.into_iter().fold(0, |h, card| {
+        copies[card.index()] += 1;
+        h ^ card_key(card, copies[card.index()] - 1)
+    })
+}
+
+pub(crate) fn zone_key(zone: Zone, cards: &[Card]) -> u64 {
+    if zone.is_ordered() {
+        cards.iter().enumerate().fold(0, |h, (i, c)| h ^ card_key(zone, *c, i))
+    } else {
+        multiset_key(cards.iter().copied(), |c, slot| card_key(zone, c, slot))
+    }
+}
+
+/// Played cards whose ally ability was used this turn
+fn allies_used(player: &Player) -> impl Iterator<Item = Card> + '_ {
+    player.hand.played.iter().zip(&player.hand.ally_used).filter(|(_, used)| **used).map(|(c, _)| *c)
+}
+
+fn allies_key(player_id: i32, player: &Player) -> u64 {
+    multiset_key(allies_used(player), |c, slot| ally_key(player_id, c, slot))
+}
+
+impl GameState {
+    /// Zobrist hash of the state: the XOR of a key for each card in each zone, each counter,
+    /// used ally ability, delayed ability and pending decision. A move only changes the keys
+    /// of what it touches, so the state keeps its hash up to date as it changes, and searches
+    /// can update one with `card_key` and `counter_key` too.
+    ///
+    /// The random generators, the clock and the events are left out. This is the value kept by the
+    /// state, for transposition tables: a state changed through its public fields must be
+    /// `rehash`ed first. `Hash` computes it again instead, so that it always agrees with `Eq`.
+    pub fn zobrist(&self) -> u64 {
+        self.hash
+    }
+
+    /// Computes the hash of the state again, after changes made through its public fields
+    pub fn rehash(&mut self) {
+        self.hash = self.full_zobrist();
+    }
+
+    /// The hash computed from every part of the state, see `zobrist`
+    pub(crate) fn full_zobrist(&self) -> u64 {
+        let mut hash = Zone::ALL.into_iter().fold(0, |h, zone| h ^ zone_key(zone, self.zone(zone)));
+        for counter in Counter::ALL {
+            hash ^= counter_key(counter, counter.value(self));
+        }
+        for (p, player) in [(0, &self.players.0), (1, &self.players.1)] {
+            hash ^= allies_key(p, player) ^ abilities_key(p, &player.hand.abilities);
+        }
+        hash ^ pending_key(&self.pending)
+    }
+
+    /// Keys to toggle when the card at `at` leaves `zone`, along with its used ally ability.
+    /// The cards above it in an ordered zone move down a slot.
+    pub(crate) fn removal_key(&self, zone: Zone, at: usize) -> u64 {
+        let cards = self.zone(zone);
+        let card = cards[at];
+        let mut hash = if zone.is_ordered() {
+            (at..cards.len()).fold(0, |h, i| h ^ card_key(zone, cards[i], i))
+                ^ (at + 1..cards.len()).fold(0, |h, i| h ^ card_key(zone, cards[i], i - 1))
+        } else {
+            card_key(zone, card, cards.iter().filter(|c| **c == card).count() - 1)
+        };
+        if let Zone::Played(p) = zone {
+            let used = self.get_player(p).ok().filter(|player| player.hand.ally_used.get(at) == Some(&true));
+            if let Some(player) = used {
+                hash ^= ally_key(p, card, allies_used(player).filter(|c| *c == card).count() - 1);
+            }
+        }
+        hash
+    }
+
+    /// Key to toggle when `card` goes on top of `zone`
+    pub(crate) fn push_key(&self, zone: Zone, card: Card) -> u64 {
+        let cards = self.zone(zone);
+        let slot = if zone.is_ordered() { cards.len() } else { cards.iter().filter(|c| **c == card).count() };
+        card_key(zone, card, slot)
+    }
+}
+
+/// Keys to toggle when player `player_id` goes from `old` to `new`: only the parts that changed are keyed again
+pub(crate) fn player_key(player_id: i32, old: &Player, new: &Player) -> u64 {
+    let zones = [
+        (Zone::Hand(player_id), &old.hand.playable, &new.hand.playable),
+        (Zone::Deck(player_id), &old.deck, &new.deck),
+        (Zone::Discard(player_id), &old.discard, &new.discard),
+        (Zone::Played(player_id), &old.hand.played, &new.hand.played),
+    ];
+    let mut hash = zones.into_iter()
+        .filter(|(_, old, new)| old != new)
+        .fold(0, |h, (zone, old, new)| h ^ zone_key(zone, old) ^ zone_key(zone, new));
+    for counter in Counter::ALL.into_iter().filter(|c| c.player() == Some(player_id)) {
+        let (before, after) = (counter.value_of(old), counter.value_of(new));
+        if before != after {
+            hash ^= counter_key(counter, before) ^ counter_key(counter, after);
+        }
+    }
+    if old.hand.played != new.hand.played || old.hand.ally_used != new.hand.ally_used {
+        hash ^= allies_key(player_id, old) ^ allies_key(player_id, new);
+    }
+    let (before, after) = (&old.hand.abilities, &new.hand.abilities);
+    if before.len() != after.len() || before.iter().zip(after).any(|(a, b)| !same_ability(a, b)) {
+        hash ^= abilities_key(player_id, before) ^ abilities_key(player_id, after);
+    }
+    hash
+}
+
+/// Two states are the same position when they only differ by the order of interchangeable cards
+/// (outside of the decks) and by what `zobrist` leaves out.
+///
+/// Actions pick cards by their index in a zone (`Play`, `Buy`, `Ally`, `Scrap` and the targets of
+/// decisions), so one action can do different things in two equal states: this equality and
+/// `Hash` suit tables of values, such as evaluations, but not tables of actions.
+impl PartialEq for GameState {
+    fn eq(&self, other: &Self) -> bool {
+        Zone::ALL.into_iter().all(|z| if z.is_ordered() {
+            self.zone(z) == other.zone(z)
+        } else {
+            counts(self.zone(z).iter().copied()) == counts(other.zone(z).iter().copied())
+        })
+            && Counter::ALL.into_iter().all(|c| c.value(self) == c.value(other))
+            && [(&self.players.0, &other.players.0), (&self.players.1, &other.players.1)].into_iter().all(|(mine, theirs)| {
+                let (a, b) = (&mine.hand.abilities, &theirs.hand.abilities);
+                counts(allies_used(mine)) == counts(allies_used(theirs))
+                    && a.len() == b.len() && a.iter().all(|ability| copies(a, ability) == copies(b, ability))
+            })
+            && self.pending.len() == other.pending.len()
+            && self.pending.iter().zip(&other.pending)
+                .all(|(a, b)| a.selected == b.selected && same_ability(a.ability, b.ability))
+    }
+}
+
+impl Eq for GameState {}
+
+impl Hash for GameState {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        state.write_u64(self.full_zobrist());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashSet;
+    use crate::gamelogic::{Action, GameLogic};
+
+    #[test]
+    fn test_transpositions() {
+        let gs = GameState::with_seed(5);
+        let hand = gs.get_player(0).unwrap().hand.playable.clone();
+        let first = GameLogic::apply(&GameLogic::apply(&gs, &Action::Play(0)).unwrap(), &Action::Play(0)).unwrap();
+        let second = GameLogic::apply(&GameLogic::apply(&gs, &Action::Play(1)).unwrap(), &Action::Play(0)).unwrap();
+        assert_eq!(first, second);
+        assert_eq!(first.zobrist(), second.zobrist());
+        assert_eq!(HashSet::from([first.clone(), second]).len(), 1);
+
+        let third = GameLogic::apply(&first, &Action::Play(0)).unwrap();
+        assert_ne!(first, third);
+        assert_ne!(first.zobrist(), third.zobrist());
+
+        // playing a card only touches its keys
+        let card = hand[0];
+        let in_hand = hand.iter().filter(|c| **c == card).count();
+        let played = GameLogic::apply(&gs, &Action::Play(0)).unwrap();
+        let trade = played.get_player(0).unwrap().hand.trade;
+        let incremental = gs.zobrist()
+            ^ card_key(Zone::Hand(0), card, in_hand - 1)
+            ^ card_key(Zone::Played(0), card, 0)
+            ^ counter_key(Counter::Trade(0), 0) ^ counter_key(Counter::Trade(0), trade)
+            ^ counter_key(Counter::Damage(0), 0) ^ counter_key(Counter::Damage(0), played.get_player(0).unwrap().hand.damage);
+        assert_eq!(incremental, played.zobrist());
+    }
+
+    #[test]
+    fn test_hash_agrees_with_eq_without_rehash() {
+        let mut changed = GameState::with_seed(5);
+        changed.players.0.hand.trade = 3;
+        let mut rehashed = changed.clone();
+        rehashed.rehash();
+        assert_eq!(changed, rehashed);
+        assert_ne!(changed.zobrist(), rehashed.zobrist());
+        assert_eq!(HashSet::from([changed, rehashed]).len(), 1);
+    }
+
+    #[test]
+    fn test_equal_states_can_index_cards_differently() {
+        let mut gs = GameState::with_seed(5);
+        gs.trade_row = vec![Card::Cutter, Card::BlobFighter];
+        gs.players.0.hand.trade = 10;
+        gs.rehash();
+        let mut swapped = gs.clone();
+        swapped.trade_row.reverse();
+        swapped.rehash();
+        assert_eq!(gs, swapped);
+        assert_eq!(gs.zobrist(), swapped.zobrist());
+
+        let bought = GameLogic::apply(&gs, &Action::Buy(0)).unwrap();
+        let swapped_bought = GameLogic::apply(&swapped, &Action::Buy(0)).unwrap();
+        assert_eq!(bought.get_player(0).unwrap().discard, vec![Card::Cutter]);
+        assert_eq!(swapped_bought.get_player(0).unwrap().discard, vec![Card::BlobFighter]);
+        assert_ne!(bought, swapped_bought);
+    }
+}
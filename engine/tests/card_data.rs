@@ -0,0 +1,76 @@
+//! Every card of the engine against `base_set.csv`, the official stats of the base set.
+use std::collections::BTreeMap;
+use star_realms_engine::abilities::{Ability, Effect};
+use star_realms_engine::cards::{Card, CardFactory};
+use star_realms_engine::star_realms::{GameState, Zone};
+
+const BASE_SET: &str = include_str!("base_set.csv");
+
+/// Writes an ability the way the reference table does
+fn notation(ability: &Ability) -> String {
+    match ability {
+        Ability::Atomic(a) => match a.effect() {
+            Effect::Trade(n) => format!("{n} trade"),
+            Effect::Damage(n) => format!("{n} combat"),
+            Effect::Authority(n) => format!("{n} authority"),
+            Effect::Draw(n) => format!("draw {n}"),
+            Effect::DrawPerFaction(f) => format!("draw per {f} card"),
+            Effect::NextShipOnTop => "next ship on top".to_string(),
+            Effect::FreeShipOnTop => "free ship on top".to_string(),
+            Effect::DestroyBase => "destroy target base".to_string(),
+            Effect::ScrapTradeRow => "scrap a card in trade row".to_string(),
+            Effect::TargetDiscard => "opponent discards".to_string(),
+            Effect::Discard => "discard".to_string(),
+            Effect::DiscardThenDraw { max } => format!("discard up to {max} then draw"),
+            Effect::ShipsGetDamage(n) => format!("ships get {n} combat"),
+            Effect::ScrapHandOrDiscard { max } => format!("scrap up to {max}"),
+            Effect::ScrapThenDraw { max } => format!("scrap up to {max} then draw"),
+            Effect::ScrapFromHand => "scrap from hand".to_string(),
+            Effect::DrawThenScrap => "draw 1 then scrap 1".to_string(),
+            Effect::CopyShip => "copy ship".to_string(),
+            Effect::AllyToAll => "ally to all".to_string(),
+        },
+        Ability::And(a, b) => format!("{} + {}", notation(a), notation(b)),
+        Ability::Or(a, b) => format!("{} | {}", notation(a), notation(b)),
+        Ability::Cond(_, a) => format!("{}?", notation(a)),
+        Ability::Delayed(a) => notation(a),
+    }
+}
+
+fn copies() -> BTreeMap<Card, usize> {
+    let gs = GameState::with_seed(0);
+    let mut copies = BTreeMap::new();
+    for zone in Zone::ALL {
+        for card in gs.zone(zone) {
+            *copies.entry(*card).or_insert(0) += 1;
+        }
+    }
+    copies
+}
+
+#[test]
+fn test_cards_match_the_official_base_set() {
+    let copies = copies();
+    let mut seen = vec![];
+    for line in BASE_SET.lines().skip_while(|l| l.starts_with('#')).skip(1) {
+        let columns: Vec<&str> = line.split(';').collect();
+        let [name, faction, cost, defense, outpost, count, on_board, on_faction, on_scrap] = columns[..] else {
+            panic!("Malformed line: {line}");
+        };
+        let card = CardFactory::by_name(name).unwrap_or_else(|| panic!("No card named {name}"));
+        let text = |v: Option<String>| v.unwrap_or("-".to_string());
+        let abilities = card.get_abilities();
+        let ability = |a: &Option<Ability>| a.as_ref().map_or(String::new(), notation);
+        assert_eq!(text(card.get_faction().map(|f| f.to_string())), faction, "{name}");
+        assert_eq!(text(card.get_cost().map(|c| c.to_string())), cost, "{name}");
+        assert_eq!(text(card.get_defense().map(|d| d.to_string())), defense, "{name}");
+        assert_eq!(text(card.is_base().then(|| if card.is_outpost() { "yes" } else { "no" }.to_string())), outpost, "{name}");
+        assert_eq!(copies.get(&card).copied().unwrap_or(0).to_string(), count, "{name}");
+        assert_eq!(ability(&abilities.on_board), on_board, "{name}");
+        assert_eq!(ability(&abilities.on_faction), on_faction, "{name}");
+        assert_eq!(ability(&abilities.on_scrap), on_scrap, "{name}");
+        seen.push(card);
+    }
+    seen.sort();
+    assert_eq!(seen, Card::ALL.to_vec(), "The table has every card once");
+}
@@ -0,0 +1,395 @@
+//! Rules conformance of every card: the exact effect of its abilities, then the cards whose
+//! abilities need a choice or interact with other cards.
+use std::ops::Add;
+use star_realms_engine::cards::{Card, Faction};
+use star_realms_engine::error::RuleError;
+use star_realms_engine::gamelogic::{Action, GameLogic, Target};
+use star_realms_engine::star_realms::GameState;
+
+/// What an action changed for the current player
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct Effect {
+    trade: i32,
+    damage: i32,
+    authority: i32,
+    drawn: i32,
+    /// Decisions to take before anything else
+    choices: i32,
+    /// Abilities kept in the hand for later
+    delayed: i32,
+    ships_on_top: i32,
+    ships_free: i32,
+    damage_per_ship: i32,
+    /// Cards the opponent discards at the start of their turn
+    discards: i32,
+}
+
+const NONE: Effect = Effect {
+    trade: 0, damage: 0, authority: 0, drawn: 0, choices: 0, delayed: 0, ships_on_top: 0, ships_free: 0, damage_per_ship: 0,
+    discards: 0,
+};
+
+impl Add for Effect {
+    type Output = Effect;
+
+    fn add(self, o: Effect) -> Effect {
+        Effect {
+            trade: self.trade + o.trade,
+            damage: self.damage + o.damage,
+            authority: self.authority + o.authority,
+            drawn: self.drawn + o.drawn,
+            choices: self.choices + o.choices,
+            delayed: self.delayed + o.delayed,
+            ships_on_top: self.ships_on_top + o.ships_on_top,
+            ships_free: self.ships_free + o.ships_free,
+            damage_per_ship: self.damage_per_ship + o.damage_per_ship,
+            discards: self.discards + o.discards,
+        }
+    }
+}
+
+fn t(trade: i32) -> Effect { Effect { trade, ..NONE } }
+fn d(damage: i32) -> Effect { Effect { damage, ..NONE } }
+fn a(authority: i32) -> Effect { Effect { authority, ..NONE } }
+fn draw(drawn: i32) -> Effect { Effect { drawn, ..NONE } }
+fn choice() -> Effect { Effect { choices: 1, ..NONE } }
+fn resolved() -> Effect { Effect { choices: -1, ..NONE } }
+fn delayed(delayed: i32) -> Effect { Effect { delayed, ..NONE } }
+fn on_top() -> Effect { Effect { ships_on_top: 1, ..NONE } }
+fn free() -> Effect { Effect { ships_free: 1, ..NONE } }
+fn per_ship(damage_per_ship: i32) -> Effect { Effect { damage_per_ship, ..NONE } }
+fn discard() -> Effect { Effect { discards: 1, ..NONE } }
+
+/// On board, ally and scrap effects of every card, the last two missing when the card has no such ability
+fn expected() -> Vec<(Card, Effect, Option<Effect>, Option<Effect>)> {
+    vec![
+        (Card::Scout, t(1), None, None),
+        (Card::Viper, d(1), None, None),
+        (Card::Explorer, t(2), None, Some(d(2))),
+        // Trade Federation
+        (Card::FederationShuttle, t(2), Some(a(4)), None),
+        (Card::Cutter, a(4) + t(2), Some(d(4)), None),
+        (Card::EmbassyYacht, a(3) + t(2), None, None),
+        (Card::Freighter, t(4), Some(on_top()), None),
+        (Card::CommandShip, a(4) + d(5) + draw(2), Some(delayed(1)), None),
+        (Card::TradeEscort, a(4) + d(4), Some(draw(1)), None),
+        (Card::Flagship, d(5) + draw(1), Some(a(5)), None),
+        (Card::TradingPost, choice(), None, Some(d(3))),
+        (Card::BarterWorld, choice(), None, Some(d(5))),
+        (Card::DefenseCenter, choice(), Some(d(2)), None),
+        (Card::CentralOffice, t(2) + on_top(), Some(draw(1)), None),
+        (Card::PortOfCall, t(3), None, Some(draw(1) + delayed(1))),
+        // Blob
+        (Card::BlobFighter, d(3), Some(draw(1)), None),
+        (Card::TradePod, t(3), Some(d(2)), None),
+        (Card::BattlePod, d(4) + delayed(1), Some(d(2)), None),
+        (Card::Ram, d(5), Some(d(2)), Some(t(3))),
+        (Card::BlobDestroyer, d(6), Some(delayed(2)), None),
+        (Card::BattleBlob, d(8), Some(draw(1)), Some(d(4))),
+        (Card::BlobCarrier, d(7), Some(on_top() + free()), None),
+        (Card::Mothership, d(6) + draw(1), Some(draw(1)), None),
+        (Card::BlobWheel, d(1), None, Some(t(3))),
+        (Card::TheHive, d(3), Some(draw(1)), None),
+        (Card::BlobWorld, choice(), None, None),
+        // Star Empire
+        (Card::ImperialFighter, d(2) + discard(), Some(d(2)), None),
+        (Card::ImperialFrigate, d(4) + discard(), Some(d(2)), Some(draw(1))),
+        (Card::SurveyShip, t(1) + draw(1), None, Some(discard())),
+        (Card::Corvette, d(1) + draw(1), Some(d(2)), None),
+        (Card::Battlecruiser, d(5) + draw(1), Some(discard()), Some(draw(1) + delayed(1))),
+        (Card::Dreadnaught, d(7) + draw(1), None, Some(d(5))),
+        (Card::SpaceStation, d(2), Some(d(2)), Some(t(4))),
+        (Card::RecyclingStation, choice(), None, None),
+        (Card::WarWorld, d(3), Some(d(4)), None),
+        (Card::RoyalRedoubt, d(3), Some(discard()), None),
+        (Card::FleetHq, per_ship(1), None, None),
+        // Machine Cult
+        (Card::TradeBot, t(1) + choice(), Some(d(2)), None),
+        (Card::MissileBot, d(2) + choice(), Some(d(2)), None),
+        (Card::SupplyBot, t(2) + choice(), Some(d(2)), None),
+        (Card::BattleStation, NONE, None, Some(d(5))),
+        (Card::PatrolMech, choice(), Some(choice()), None),
+        (Card::StealthNeedle, choice(), None, None),
+        (Card::BattleMech, d(4) + choice(), Some(draw(1)), None),
+        (Card::MissileMech, d(6) + delayed(1), Some(draw(1)), None),
+        (Card::MechWorld, NONE, None, None),
+        (Card::BrainWorld, delayed(1), None, None),
+        (Card::MachineBase, delayed(1), None, None),
+        (Card::Junkyard, choice(), None, None),
+    ]
+}
+
+/// The first player to move holds `hand` and has `played` in play, with known cards in the
+/// deck and discard pile. The opponent has a Blob Wheel in play.
+fn table(hand: &[Card], played: &[Card]) -> GameState {
+    let mut gs = GameState::with_seed(11);
+    gs.players.0.hand.playable = hand.to_vec();
+    gs.players.0.hand.played = played.to_vec();
+    gs.players.0.deck = vec![Card::Viper; 6];
+    gs.players.0.discard = vec![Card::Scout, Card::Scout];
+    gs.players.1.hand.played = vec![Card::BlobWheel];
+    gs.rehash();
+    gs
+}
+
+fn play(gs: &GameState, actions: &[Action]) -> GameState {
+    actions.iter().fold(gs.clone(), |gs, action| {
+        GameLogic::apply(&gs, action).unwrap_or_else(|e| panic!("{action}: {e}"))
+    })
+}
+
+/// Effect of `action` on the first player, who puts `from_hand` cards out of the hand with it
+fn effect_of(gs: &GameState, action: Action, from_hand: i32) -> Effect {
+    let after = play(gs, &[action]);
+    let (me, next) = (&gs.players.0, &after.players.0);
+    Effect {
+        trade: next.hand.trade - me.hand.trade,
+        damage: next.hand.damage - me.hand.damage,
+        authority: next.authority - me.authority,
+        drawn: next.hand.playable.len() as i32 - me.hand.playable.len() as i32 + from_hand,
+        choices: after.pending.len() as i32 - gs.pending.len() as i32,
+        delayed: next.hand.abilities.len() as i32 - me.hand.abilities.len() as i32,
+        ships_on_top: next.hand.next_n_ships_on_top - me.hand.next_n_ships_on_top,
+        ships_free: next.hand.next_n_ships_free - me.hand.next_n_ships_free,
+        damage_per_ship: next.hand.damage_per_ship - me.hand.damage_per_ship,
+        discards: after.players.1.hand.discards - gs.players.1.hand.discards,
+    }
+}
+
+/// A card of the same faction, without ally ability
+fn ally_of(card: Card) -> Card {
+    match card.get_faction() {
+        Some(Faction::Trade) => Card::FederationShuttle,
+        Some(Faction::Blob) => Card::BlobWheel,
+        Some(Faction::Star) => Card::SurveyShip,
+        Some(Faction::Machine) => Card::MechWorld,
+        None => Card::Scout,
+    }
+}
+
+#[test]
+fn test_every_card_effect() {
+    let expected = expected();
+    assert_eq!(expected.len(), Card::ALL.len());
+    for (card, on_board, on_faction, on_scrap) in expected {
+        assert_eq!(effect_of(&table(&[card], &[]), Action::Play(0), 1), on_board, "{card:?} played");
+
+        let allied = table(&[], &[card, ally_of(card)]);
+        match on_faction {
+            Some(e) => assert_eq!(effect_of(&allied, Action::Ally(0), 0), e, "{card:?} ally"),
+            None => assert_eq!(GameLogic::check(&allied, &Action::Ally(0)), Err(RuleError::NoSuchAbility(card.get_name()))),
+        }
+
+        let in_play = table(&[], &[card]);
+        match on_scrap {
+            Some(e) => assert_eq!(effect_of(&in_play, Action::Scrap(0), 0), e, "{card:?} scrapped"),
+            None => assert_eq!(GameLogic::check(&in_play, &Action::Scrap(0)), Err(RuleError::NoSuchAbility(card.get_name()))),
+        }
+    }
+}
+
+#[test]
+fn test_ally_needs_a_card_of_the_same_faction() {
+    let alone = table(&[], &[Card::Cutter, Card::BlobFighter]);
+    assert_eq!(GameLogic::check(&alone, &Action::Ally(0)), Err(RuleError::NoAlly("Cutter".to_string())));
+    let gs = play(&table(&[], &[Card::Cutter, Card::FederationShuttle]), &[Action::Ally(0)]);
+    assert_eq!(GameLogic::check(&gs, &Action::Ally(0)), Err(RuleError::AbilityAlreadyUsed));
+}
+
+#[test]
+fn test_or_abilities() {
+    let gs = play(&table(&[Card::TradingPost], &[]), &[Action::Play(0)]);
+    assert_eq!(effect_of(&gs, Action::Choose(0), 0), a(1) + resolved());
+    assert_eq!(effect_of(&gs, Action::Choose(1), 0).trade, 1);
+
+    let gs = play(&table(&[Card::PatrolMech], &[]), &[Action::Play(0)]);
+    assert_eq!(effect_of(&gs, Action::Choose(0), 0).trade, 3);
+    assert_eq!(effect_of(&gs, Action::Choose(1), 0).damage, 5);
+
+    // neither branch
+    assert!(GameLogic::legal_actions(&gs).contains(&Action::Decline));
+    assert_eq!(effect_of(&gs, Action::Decline, 0), resolved());
+    assert_eq!(GameLogic::check(&gs, &Action::Confirm), Err(RuleError::WrongPhase("Choose one of the two abilities or decline")));
+}
+
+#[test]
+fn test_blob_world_draws_a_card_per_blob_card_played() {
+    let gs = play(&table(&[Card::BlobWorld], &[Card::BlobFighter, Card::Ram, Card::Cutter]), &[Action::Play(0)]);
+    assert_eq!(effect_of(&gs, Action::Choose(0), 0).damage, 5);
+    assert_eq!(effect_of(&gs, Action::Choose(1), 0).drawn, 2);
+
+    // a Stealth Needle copying a Blob ship is a Blob card too
+    let gs = play(&table(&[Card::Ram, Card::StealthNeedle, Card::BlobWorld], &[]), &[Action::Play(0), Action::Play(0)]);
+    let gs = play(&gs, &[Action::Select(Target::Played(0)), Action::Play(0)]);
+    assert_eq!(effect_of(&gs, Action::Choose(1), 0).drawn, 2);
+}
+
+#[test]
+fn test_embassy_yacht_draws_with_two_bases() {
+    let one = table(&[Card::EmbassyYacht], &[Card::TradingPost]);
+    assert_eq!(effect_of(&one, Action::Play(0), 1).drawn, 0);
+    let two = table(&[Card::EmbassyYacht], &[Card::TradingPost, Card::BarterWorld]);
+    assert_eq!(effect_of(&two, Action::Play(0), 1), a(3) + t(2) + draw(2));
+}
+
+#[test]
+fn test_fleet_hq_gives_damage_for_each_ship() {
+    let gs = play(&table(&[Card::FleetHq, Card::Scout, Card::TradingPost], &[Card::Viper]), &[Action::Play(0)]);
+    assert_eq!(gs.players.0.hand.damage, 1);
+    // ships played after it get the bonus too, bases do not
+    assert_eq!(effect_of(&gs, Action::Play(0), 1), t(1) + d(1));
+    assert_eq!(effect_of(&gs, Action::Play(1), 1).damage, 0);
+
+    // and it keeps working on the next turns
+    let mut gs = play(&gs, &[Action::EndTurn]);
+    gs = play(&gs, &[Action::EndTurn]);
+    assert_eq!(gs.players.0.hand.damage_per_ship, 1);
+    let ships = gs.players.0.hand.playable.iter().filter(|c| !c.is_base()).count() as i32;
+    let all = (0..gs.players.0.hand.playable.len()).map(|_| Action::Play(0)).collect::<Vec<_>>();
+    let played = play(&gs, &all);
+    let vipers = gs.players.0.hand.playable.iter().filter(|c| **c == Card::Viper).count() as i32;
+    assert_eq!(played.players.0.hand.damage, ships + vipers);
+}
+
+#[test]
+fn test_target_discard() {
+    let gs = play(&table(&[Card::ImperialFighter, Card::ImperialFrigate], &[]), &[Action::Play(0), Action::Play(0)]);
+    // the opponent discards at the start of their turn, and chooses the cards
+    assert!(gs.pending.is_empty());
+    assert_eq!(gs.players.1.hand.playable.len(), 5);
+    let gs = play(&gs, &[Action::EndTurn]);
+    assert_eq!(gs.players.1.hand.discards, 0);
+    assert_eq!(gs.pending.len(), 2);
+    assert_eq!(GameLogic::check(&gs, &Action::Decline), Err(RuleError::TooFewTargets(1)));
+    let picks: Vec<Action> = (0..5).map(|i| Action::Select(Target::Playable(i))).collect();
+    assert_eq!(GameLogic::legal_actions(&gs), picks);
+    let discarded = [gs.players.1.hand.playable[2], gs.players.1.hand.playable[0]];
+    let gs = play(&gs, &[Action::Select(Target::Playable(2)), Action::Select(Target::Playable(0))]);
+    assert_eq!(gs.players.1.hand.playable.len(), 3);
+    assert!(gs.pending.is_empty());
+    // the cards go to the discard pile of their owner
+    assert_eq!(gs.players.1.discard[gs.players.1.discard.len() - 2..], discarded);
+    assert!(gs.scrap.is_empty());
+}
+
+#[test]
+fn test_scrap_from_hand_or_discard() {
+    let gs = play(&table(&[Card::TradeBot, Card::Viper], &[]), &[Action::Play(0)]);
+    assert!(GameLogic::check(&gs, &Action::Select(Target::TradeRow(0))).is_err());
+    let gs = play(&gs, &[Action::Select(Target::Discarded(1)), Action::Confirm]);
+    assert_eq!(gs.players.0.discard, vec![Card::Scout]);
+    assert_eq!(gs.scrap, vec![Card::Scout]);
+}
+
+#[test]
+fn test_destroy_target_base() {
+    let gs = play(&table(&[], &[Card::CommandShip, Card::FederationShuttle]), &[Action::Ally(0), Action::Activate(0)]);
+    let gs = play(&gs, &[Action::Select(Target::EnemyBase(0))]);
+    assert!(gs.players.1.hand.played.is_empty());
+    assert_eq!(gs.players.1.discard.last(), Some(&Card::BlobWheel));
+    assert!(gs.scrap.is_empty());
+}
+
+#[test]
+fn test_scrapped_explorer_goes_back_to_its_pile() {
+    let gs = table(&[], &[Card::Explorer]);
+    let explorers = gs.explorers.len();
+    let gs = play(&gs, &[Action::Scrap(0)]);
+    assert_eq!(gs.explorers.len(), explorers + 1);
+    assert!(gs.scrap.is_empty());
+}
+
+#[test]
+fn test_scrap_trade_row_refills_it() {
+    let gs = play(&table(&[Card::BattlePod], &[]), &[Action::Play(0), Action::Activate(0)]);
+    let row = gs.trade_row.clone();
+    let gs = play(&gs, &[Action::Select(Target::TradeRow(2))]);
+    assert_eq!(gs.scrap, vec![row[2]]);
+    assert_eq!(gs.trade_row.len(), 5);
+    assert_eq!(gs.trade_row[..2], row[..2]);
+    assert_eq!(gs.trade_row[3..], row[3..]);
+}
+
+#[test]
+fn test_acquired_ships_on_top() {
+    let mut gs = play(&table(&[], &[Card::BlobCarrier, Card::BlobWheel]), &[Action::Ally(0)]);
+    gs.trade_row = vec![Card::TradingPost, Card::Dreadnaught];
+    gs.rehash();
+    // the free ship is a ship only
+    assert_eq!(GameLogic::check(&gs, &Action::Buy(0)), Err(RuleError::NotEnoughTrade));
+    let bought = play(&gs, &[Action::Buy(1)]);
+    assert_eq!(bought.players.0.hand.trade, 0);
+    assert_eq!(bought.players.0.deck.last(), Some(&Card::Dreadnaught));
+
+    let mut gs = play(&table(&[], &[Card::Freighter, Card::FederationShuttle]), &[Action::Ally(0)]);
+    gs.trade_row = vec![Card::Cutter];
+    gs.players.0.hand.trade = 2;
+    gs.rehash();
+    let bought = play(&gs, &[Action::Buy(0)]);
+    assert_eq!(bought.players.0.deck.last(), Some(&Card::Cutter));
+    assert_eq!(bought.players.0.hand.trade, 0);
+}
+
+#[test]
+fn test_recycling_station_discards_then_draws() {
+    let gs = play(&table(&[Card::RecyclingStation, Card::Scout, Card::Viper], &[]), &[Action::Play(0), Action::Choose(1)]);
+    let gs = play(&gs, &[Action::Activate(0), Action::Select(Target::Playable(0)), Action::Select(Target::Playable(1)), Action::Confirm]);
+    assert_eq!(gs.players.0.hand.playable.len(), 2);
+    assert_eq!(gs.players.0.discard, vec![Card::Scout, Card::Scout, Card::Scout, Card::Viper]);
+    assert!(gs.scrap.is_empty());
+}
+
+#[test]
+fn test_brain_world_scraps_then_draws() {
+    let gs = play(&table(&[Card::BrainWorld, Card::Viper], &[]), &[Action::Play(0), Action::Activate(0)]);
+    let gs = play(&gs, &[Action::Select(Target::Playable(0)), Action::Select(Target::Discarded(0)), Action::Confirm]);
+    assert_eq!(gs.scrap, vec![Card::Viper, Card::Scout]);
+    assert_eq!(gs.players.0.hand.playable, vec![Card::Viper, Card::Viper]);
+}
+
+#[test]
+fn test_machine_base_draws_then_scraps() {
+    let gs = play(&table(&[Card::MachineBase], &[]), &[Action::Play(0), Action::Activate(0)]);
+    assert_eq!(gs.players.0.hand.playable, vec![Card::Viper]);
+    assert_eq!(gs.pending.len(), 1);
+    // the scrap is not optional
+    assert_eq!(GameLogic::check(&gs, &Action::Decline), Err(RuleError::TooFewTargets(1)));
+    assert_eq!(GameLogic::check(&gs, &Action::Confirm), Err(RuleError::TooFewTargets(1)));
+    assert!(!GameLogic::legal_actions(&gs).contains(&Action::Decline));
+    let gs = play(&gs, &[Action::Select(Target::Playable(0)), Action::Confirm]);
+    assert_eq!(gs.scrap, vec![Card::Viper]);
+
+    // unless there is nothing left in hand
+    let mut empty = play(&table(&[Card::MachineBase], &[]), &[Action::Play(0), Action::Activate(0)]);
+    empty.players.0.hand.playable.clear();
+    assert_eq!(GameLogic::check(&empty, &Action::Decline), Ok(()));
+}
+
+#[test]
+fn test_stealth_needle_copies_an_ally_ship() {
+    let gs = play(&table(&[Card::Ram, Card::StealthNeedle], &[]), &[Action::Play(0), Action::Play(0)]);
+    // it cannot copy itself
+    assert!(GameLogic::check(&gs, &Action::Select(Target::Played(1))).is_err());
+    assert_eq!(effect_of(&gs, Action::Select(Target::Played(0)), 0), d(5) + resolved());
+
+    // the copy is a Blob ship: an ally of the Ram, with its ally and scrap abilities
+    let gs = play(&gs, &[Action::Select(Target::Played(0))]);
+    assert_eq!(effect_of(&gs, Action::Ally(0), 0), d(2));
+    assert_eq!(effect_of(&gs, Action::Ally(1), 0), d(2));
+    assert_eq!(effect_of(&gs, Action::Scrap(1), 0), t(3));
+
+    // and no card is added to the game
+    let next = play(&gs, &[Action::EndTurn]);
+    assert_eq!(next.players.0.discard.iter().filter(|c| **c == Card::Ram).count(), 1);
+    assert_eq!(next.players.0.hand.copied, None);
+}
+
+#[test]
+fn test_mech_world_is_an_ally_of_every_faction() {
+    for ship in [Card::Cutter, Card::BlobFighter, Card::Corvette, Card::TradeBot] {
+        let alone = table(&[], &[ship]);
+        assert_eq!(GameLogic::check(&alone, &Action::Ally(0)), Err(RuleError::NoAlly(ship.get_name())));
+        let gs = table(&[], &[ship, Card::MechWorld]);
+        assert_eq!(GameLogic::check(&gs, &Action::Ally(0)), Ok(()), "{ship:?}");
+    }
+}
@@ -0,0 +1,104 @@
+//! Random legal games, checking the rules invariants after every step. A failing game is shrunk
+//! to the shortest sequence of choices that still breaks an invariant.
+use std::collections::BTreeMap;
+use proptest::collection::vec;
+use proptest::prelude::*;
+use star_realms_engine::cards::Card;
+use star_realms_engine::events::Event;
+use star_realms_engine::gamelogic::{Action, GameLogic};
+use star_realms_engine::star_realms::{GameState, Zone};
+
+const MAX_STEPS: usize = 600;
+
+/// Number of copies of each card, all zones together
+fn census(gs: &GameState) -> BTreeMap<Card, usize> {
+    let mut counts = BTreeMap::new();
+    for zone in Zone::ALL {
+        for card in gs.zone(zone) {
+            *counts.entry(*card).or_insert(0) += 1;
+        }
+    }
+    counts
+}
+
+fn check(before: &GameState, action: Action, after: &GameState, events: &[Event], cards: &BTreeMap<Card, usize>)
+    -> Result<(), TestCaseError> {
+    let census = census(after);
+    let changed: Vec<_> = Card::ALL.iter()
+        .map(|c| (c, census.get(c).copied().unwrap_or(0) as i32 - cards.get(c).copied().unwrap_or(0) as i32))
+        .filter(|(_, n)| *n != 0)
+        .collect();
+    prop_assert!(changed.is_empty(), "cards appeared or vanished: {:?}", changed);
+    prop_assert!(after.trade_row.len() <= 5, "{} cards in the trade row", after.trade_row.len());
+    let mut rehashed = after.clone();
+    rehashed.rehash();
+    prop_assert_eq!(after.zobrist(), rehashed.zobrist(), "hash out of date after {:?}", action);
+
+    let me = before.turn % 2;
+    for p in 0..2 {
+        let (old, new) = (before.get_player(p).unwrap(), after.get_player(p).unwrap());
+        prop_assert!(new.hand.trade >= 0 && new.hand.damage >= 0, "player {p} has {} trade and {} damage",
+            new.hand.trade, new.hand.damage);
+        // authority is only gained through abilities and lost through attacks
+        let gained: i32 = events.iter().map(|e| match e {
+            Event::AuthorityGained { player, amount } if *player == p => *amount,
+            Event::DamageDealt { player, amount } if *player == p => -amount,
+            _ => 0,
+        }).sum();
+        prop_assert_eq!(new.authority - old.authority, gained, "authority of player {}", p);
+    }
+    for event in events {
+        if let Event::DamageDealt { player, amount } = event {
+            prop_assert_eq!(*player, 1 - me);
+            prop_assert_eq!(*amount, before.get_player(me).unwrap().hand.damage);
+        }
+    }
+
+    // only the active player acts, and ending the turn hands over to the other one
+    let ends_turn = action == Action::EndTurn && before.pending.is_empty();
+    prop_assert_eq!(after.turn, before.turn + ends_turn as i32);
+    for event in events {
+        if let Event::CardPlayed { player, .. } | Event::CardAcquired { player, .. } | Event::TurnEnded { player } = event {
+            prop_assert_eq!(*player, me, "{} during the turn of player {}", event, me);
+        }
+    }
+    let waiting = after.opponent_player();
+    prop_assert!(waiting.hand.trade == 0 && waiting.hand.damage == 0, "the waiting player has trade or damage");
+    prop_assert!(waiting.hand.abilities.is_empty(), "the waiting player has abilities");
+    Ok(())
+}
+
+/// Plays the game of `seed`, the legal action taken at each step given by `choices`
+fn play(seed: u64, choices: &[u16]) -> Result<(), TestCaseError> {
+    let mut gs = GameState::with_seed(seed);
+    let cards = census(&gs);
+    let mut played = vec![];
+    for choice in choices {
+        if gs.is_over() {
+            break;
+        }
+        let actions = GameLogic::legal_actions(&gs);
+        prop_assert!(!actions.is_empty(), "no legal action after {:?}", played);
+        let action = actions[*choice as usize % actions.len()];
+        played.push(action);
+        let (next, events) = GameLogic::step(&gs, &action)
+            .map_err(|e| TestCaseError::fail(format!("legal action {action} failed with {e} after {played:?}")))?;
+        check(&gs, action, &next, &events, &cards)
+            .map_err(|e| TestCaseError::fail(format!("{e} after {played:?}")))?;
+        gs = next;
+    }
+    Ok(())
+}
+
+proptest! {
+    #[test]
+    fn test_random_games_keep_invariants(seed in any::<u64>(), choices in vec(any::<u16>(), 0..MAX_STEPS)) {
+        play(seed, &choices)?;
+    }
+
+    /// Mostly low choices: playing cards and using abilities rather than ending the turn
+    #[test]
+    fn test_busy_turns_keep_invariants(seed in any::<u64>(), choices in vec(0u16..6, 0..MAX_STEPS)) {
+        play(seed, &choices)?;
+    }
+}
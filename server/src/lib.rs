@@ -0,0 +1,4 @@
+//! Authoritative game server: it owns every `GameState` and clients only send actions.
+//! The protocol is in `star_realms_engine::protocol`.
+pub mod lobby;
+pub mod server;
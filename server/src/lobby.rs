@@ -0,0 +1,374 @@
+use std::collections::HashMap;
+use rand::prelude::StdRng;
+use rand::{Rng, SeedableRng};
+use star_realms_engine::clock::TimeControl;
+use star_realms_engine::gamelogic::Action;
+use star_realms_engine::protocol::{ClientMessage, ServerMessage};
+use star_realms_engine::replay::Replay;
+use star_realms_engine::star_realms::GameState;
+use star_realms_engine::view::TableView;
+
+pub type ClientId = u64;
+
+/// Milliseconds since the start of the server
+pub type Millis = u64;
+
+/// Messages to deliver, with their receiver
+pub type Outbox = Vec<(ClientId, ServerMessage)>;
+
+const CODE_LETTERS: &[u8] = b"ABCDEFGHJKLMNPQRSTUVWXYZ";
+const CODE_LENGTH: usize = 4;
+/// How long a lobby nobody is connected to waits for its players to come back
+pub const ABANDONED_AFTER: Millis = 10 * 60 * 1000;
+/// Lobbies a client can have open at once
+pub const MAX_LOBBIES_PER_CLIENT: usize = 3;
+
+/// A taken seat. The token lets its owner take it back from a new connection.
+struct Seat {
+    token: String,
+    client: Option<ClientId>,
+}
+
+/// One game and the people looking at it
+struct Lobby {
+    state: GameState,
+    record: Replay,
+    seats: [Option<Seat>; 2],
+    spectators: Vec<ClientId>,
+    /// When the clock of the current player started running
+    turn_start: Millis,
+    /// Who opened the lobby
+    creator: ClientId,
+    /// Last time someone was connected to the lobby, see `ABANDONED_AFTER`
+    last_seen: Millis,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Role {
+    Player(i32),
+    Spectator,
+}
+
+impl Lobby {
+    fn new(seed: u64, clock: Option<TimeControl>, creator: ClientId, now: Millis) -> Lobby {
+        let mut record = Replay::new(seed, ["remote".to_string(), "remote".to_string()]);
+        record.clock = clock;
+        Lobby {
+            state: record.initial_state(),
+            record,
+            seats: [None, None],
+            spectators: vec![],
+            turn_start: 0,
+            creator,
+            last_seen: now,
+        }
+    }
+
+    /// Whether the current player's clock is running: it waits for both players
+    fn clock_running(&self) -> bool {
+        self.state.clock.is_some() && self.seats.iter().all(|s| s.is_some()) && !self.state.is_over()
+    }
+
+    /// Whether the current player ran out of time at `now`
+    fn timed_out(&self, now: Millis) -> bool {
+        let elapsed = now.saturating_sub(self.turn_start);
+        self.clock_running() && self.state.clock.is_some_and(|c| c.remaining_ms[(self.state.turn % 2) as usize] <= elapsed)
+    }
+
+    /// Plays `action` for the current player at `now`, or the timeout if their time is up
+    fn play(&mut self, action: Action, now: Millis) -> Result<(), String> {
+        let action = if self.timed_out(now) { Action::Timeout } else { action };
+        self.state = match self.state.clock {
+            Some(_) => self.record.apply_timed(&self.state, action, now.saturating_sub(self.turn_start))?,
+            None => self.record.apply(&self.state, action)?,
+        };
+        self.turn_start = now;
+        Ok(())
+    }
+
+    fn connected(&self) -> Vec<(ClientId, Role)> {
+        let players = self.seats.iter().enumerate()
+            .filter_map(|(i, s)| s.as_ref().and_then(|s| s.client).map(|c| (c, Role::Player(i as i32))));
+        players.chain(self.spectators.iter().map(|c| (*c, Role::Spectator))).collect()
+    }
+
+    fn state_for(&self, role: Role, now: Millis) -> ServerMessage {
+        let seat = match role {
+            Role::Player(seat) => Some(seat),
+            Role::Spectator => None,
+        };
+        let mut view = TableView::of(&self.state, seat);
+        if let Some(clock) = view.clock.as_mut().filter(|_| self.clock_running()) {
+            clock.spend(self.state.turn % 2, now.saturating_sub(self.turn_start));
+        }
+        ServerMessage::State { view: Box::new(view) }
+    }
+
+    /// The game, to everyone but `except`
+    fn broadcast(&self, message: impl Fn(Role) -> ServerMessage, except: Option<ClientId>) -> Outbox {
+        self.connected().into_iter()
+            .filter(|(c, _)| Some(*c) != except)
+            .map(|(c, role)| (c, message(role)))
+            .collect()
+    }
+}
+
+/// Every lobby of the server and who is where. It only turns messages into messages, the
+/// connections are handled by `server`.
+pub struct Hub {
+    lobbies: HashMap<String, Lobby>,
+    clients: HashMap<ClientId, (String, Role)>,
+    rng: StdRng,
+}
+
+fn error(client: ClientId, message: &str) -> Outbox {
+    vec![(client, ServerMessage::Error { message: message.to_string() })]
+}
+
+impl Hub {
+    pub fn new(seed: u64) -> Hub {
+        Hub {
+            lobbies: HashMap::new(),
+            clients: HashMap::new(),
+            rng: StdRng::seed_from_u64(seed),
+        }
+    }
+
+    /// Answers a message received at `now`
+    pub fn handle(&mut self, client: ClientId, message: ClientMessage, now: Millis) -> Outbox {
+        match message {
+            ClientMessage::Create { clock } => {
+                if self.lobbies.values().filter(|l| l.creator == client).count() >= MAX_LOBBIES_PER_CLIENT {
+                    return error(client, "Too many lobbies open, wait for one to close");
+                }
+                let mut outbox = self.disconnect(client);
+                let code = loop {
+                    let code: String = (0..CODE_LENGTH)
+                        .map(|_| CODE_LETTERS[self.rng.gen_range(0..CODE_LETTERS.len())] as char)
+                        .collect();
+                    if !self.lobbies.contains_key(&code) {
+                        break code;
+                    }
+                };
+                self.lobbies.insert(code.clone(), Lobby::new(self.rng.gen(), clock, client, now));
+                outbox.append(&mut self.sit(client, &code, 0, now));
+                outbox
+            }
+            ClientMessage::Join { code } => {
+                let code = code.trim().to_uppercase();
+                let seat = match self.lobbies.get(&code) {
+                    None => return error(client, "No lobby with this code"),
+                    Some(lobby) => match lobby.seats.iter().position(|s| s.is_none()) {
+                        Some(seat) => seat,
+                        None => return error(client, "The lobby is full, join as a spectator"),
+                    },
+                };
+                let mut outbox = self.disconnect(client);
+                outbox.append(&mut self.sit(client, &code, seat as i32, now));
+                outbox
+            }
+            ClientMessage::Spectate { code } => {
+                let code = code.trim().to_uppercase();
+                if !self.lobbies.contains_key(&code) {
+                    return error(client, "No lobby with this code");
+                }
+                let mut outbox = self.disconnect(client);
+                let lobby = self.lobbies.get_mut(&code).unwrap();
+                lobby.spectators.push(client);
+                lobby.last_seen = now;
+                self.clients.insert(client, (code.clone(), Role::Spectator));
+                outbox.push((client, ServerMessage::Joined { code, seat: None, token: None }));
+                outbox.push((client, lobby.state_for(Role::Spectator, now)));
+                outbox
+            }
+            ClientMessage::Reconnect { code, token } => {
+                let code = code.trim().to_uppercase();
+                let seat = self.lobbies.get(&code)
+                    .and_then(|l| l.seats.iter().position(|s| s.as_ref().is_some_and(|s| s.token == token)));
+                let seat = match seat {
+                    Some(seat) => seat,
+                    None => return error(client, "No seat to take back with this token"),
+                };
+                let mut outbox = self.disconnect(client);
+                // the seat may still be held by a connection that did not notice it was lost
+                let previous = self.lobbies[&code].seats[seat].as_ref().and_then(|s| s.client);
+                if let Some(previous) = previous {
+                    self.clients.remove(&previous);
+                }
+                let lobby = self.lobbies.get_mut(&code).unwrap();
+                lobby.seats[seat].as_mut().unwrap().client = Some(client);
+                lobby.last_seen = now;
+                let role = Role::Player(seat as i32);
+                self.clients.insert(client, (code.clone(), role));
+                outbox.push((client, ServerMessage::Joined { code, seat: Some(seat as i32), token: Some(token) }));
+                outbox.push((client, lobby.state_for(role, now)));
+                outbox.append(&mut lobby.broadcast(|_| ServerMessage::Presence { seat: seat as i32, connected: true }, Some(client)));
+                outbox
+            }
+            ClientMessage::Action { action } => {
+                let (code, role) = match self.clients.get(&client) {
+                    Some(c) => c.clone(),
+                    None => return error(client, "Join a lobby first"),
+                };
+                let lobby = self.lobbies.get_mut(&code).unwrap();
+                let seat = match role {
+                    Role::Player(seat) => seat,
+                    Role::Spectator => return error(client, "Spectators cannot play"),
+                };
+                if lobby.seats.iter().any(|s| s.is_none()) {
+                    return error(client, "Waiting for an opponent");
+                }
+                if lobby.state.turn % 2 != seat {
+                    return error(client, "Not your turn");
+                }
+                match lobby.play(action, now) {
+                    Ok(()) => lobby.broadcast(|role| lobby.state_for(role, now), None),
+                    Err(e) => error(client, &e),
+                }
+            }
+        }
+    }
+
+    /// Plays the timeout of every player whose time ran out at `now`, even when they are away,
+    /// and closes the lobbies nobody came back to for `ABANDONED_AFTER`
+    pub fn tick(&mut self, now: Millis) -> Outbox {
+        let mut outbox = vec![];
+        for lobby in self.lobbies.values_mut().filter(|l| l.timed_out(now)) {
+            if lobby.play(Action::Timeout, now).is_ok() {
+                outbox.append(&mut lobby.broadcast(|role| lobby.state_for(role, now), None));
+            }
+        }
+        for lobby in self.lobbies.values_mut().filter(|l| !l.connected().is_empty()) {
+            lobby.last_seen = now;
+        }
+        self.lobbies.retain(|_, l| now.saturating_sub(l.last_seen) < ABANDONED_AFTER);
+        outbox
+    }
+
+    /// Takes `seat` of the lobby `code` for a new player
+    fn sit(&mut self, client: ClientId, code: &str, seat: i32, now: Millis) -> Outbox {
+        let token = format!("{:016x}", self.rng.gen::<u64>());
+        let lobby = self.lobbies.get_mut(code).unwrap();
+        lobby.seats[seat as usize] = Some(Seat { token: token.clone(), client: Some(client) });
+        lobby.turn_start = now;
+        lobby.last_seen = now;
+        self.clients.insert(client, (code.to_string(), Role::Player(seat)));
+        let mut outbox = vec![(client, ServerMessage::Joined { code: code.to_string(), seat: Some(seat), token: Some(token) })];
+        outbox.append(&mut lobby.broadcast(|role| lobby.state_for(role, now), None));
+        outbox
+    }
+
+    /// Forgets the connection of `client`. Its seat stays reserved for a reconnection, until
+    /// the lobby is abandoned, see `tick`.
+    pub fn disconnect(&mut self, client: ClientId) -> Outbox {
+        let (code, role) = match self.clients.remove(&client) {
+            Some(c) => c,
+            None => return vec![],
+        };
+        let lobby = self.lobbies.get_mut(&code).unwrap();
+        let outbox = match role {
+            Role::Player(seat) => {
+                if let Some(s) = lobby.seats[seat as usize].as_mut() {
+                    s.client = None;
+                }
+                lobby.broadcast(|_| ServerMessage::Presence { seat, connected: false }, None)
+            }
+            Role::Spectator => {
+                lobby.spectators.retain(|c| *c != client);
+                vec![]
+            }
+        };
+        if lobby.state.is_over() && lobby.connected().is_empty() {
+            self.lobbies.remove(&code);
+        }
+        outbox
+    }
+
+    /// The record of a game, for debugging and tests
+    pub fn record(&self, code: &str) -> Option<&Replay> {
+        self.lobbies.get(code).map(|l| &l.record)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn joined(outbox: &Outbox) -> (String, String) {
+        match &outbox[0].1 {
+            ServerMessage::Joined { code, token: Some(token), .. } => (code.clone(), token.clone()),
+            m => panic!("Unexpected message {m:?}"),
+        }
+    }
+
+    #[test]
+    fn test_seats_and_turns() {
+        let mut hub = Hub::new(1);
+        let (code, token) = joined(&hub.handle(1, ClientMessage::Create { clock: None }, 0));
+        let end = ClientMessage::Action { action: Action::EndTurn };
+        assert!(matches!(hub.handle(1, end.clone(), 0)[0].1, ServerMessage::Error { .. }));
+
+        hub.handle(2, ClientMessage::Join { code: code.to_lowercase() }, 0);
+        assert!(matches!(hub.handle(3, ClientMessage::Join { code: code.clone() }, 0)[0].1, ServerMessage::Error { .. }));
+        assert!(matches!(hub.handle(2, end.clone(), 0)[0].1, ServerMessage::Error { .. }));
+        // both players get the new state
+        assert_eq!(hub.handle(1, end.clone(), 0).len(), 2);
+        assert_eq!(hub.record(&code).unwrap().steps.len(), 1);
+
+        assert_eq!(hub.disconnect(1), vec![(2, ServerMessage::Presence { seat: 0, connected: false })]);
+        let wrong = ClientMessage::Reconnect { code: code.clone(), token: "0".to_string() };
+        assert!(matches!(hub.handle(4, wrong, 0)[0].1, ServerMessage::Error { .. }));
+        let outbox = hub.handle(4, ClientMessage::Reconnect { code, token }, 0);
+        assert!(matches!(&outbox[1].1, ServerMessage::State { view } if view.seat == Some(0) && view.turn == 1));
+        assert_eq!(outbox[2], (2, ServerMessage::Presence { seat: 0, connected: true }));
+    }
+
+    #[test]
+    fn test_clock() {
+        let mut hub = Hub::new(2);
+        let clock = Some("10+1 end".parse().unwrap());
+        let (code, _) = joined(&hub.handle(1, ClientMessage::Create { clock }, 0));
+        // the clock waits for the opponent
+        hub.handle(2, ClientMessage::Join { code: code.clone() }, 5_000);
+        let remaining = |outbox: &[(ClientId, ServerMessage)]| match &outbox[0].1 {
+            ServerMessage::State { view } => view.clock.unwrap().remaining_ms,
+            m => panic!("Unexpected message {m:?}"),
+        };
+        assert_eq!(remaining(&hub.handle(3, ClientMessage::Spectate { code: code.clone() }, 8_000)[1..]), [7_000, 10_000]);
+        assert_eq!(remaining(&hub.handle(1, ClientMessage::Action { action: Action::Play(0) }, 9_000)), [6_000, 10_000]);
+
+        assert!(hub.tick(14_000).is_empty());
+        let outbox = hub.tick(15_000);
+        assert_eq!(outbox.len(), 3);
+        assert_eq!(remaining(&outbox), [1_000, 10_000]);
+        let steps = &hub.record(&code).unwrap().steps;
+        assert_eq!((steps[1].action, steps[1].elapsed_ms), (Action::Timeout, Some(6_000)));
+
+        // too late, the action turns into a timeout
+        hub.handle(2, ClientMessage::Action { action: Action::Play(0) }, 30_000);
+        assert_eq!(hub.record(&code).unwrap().steps[2].action, Action::Timeout);
+    }
+
+    #[test]
+    fn test_abandoned_lobbies() {
+        let mut hub = Hub::new(3);
+        let (code, token) = joined(&hub.handle(1, ClientMessage::Create { clock: None }, 0));
+        hub.disconnect(1);
+        hub.tick(ABANDONED_AFTER - 1);
+        assert!(hub.record(&code).is_some());
+        // coming back in time keeps the lobby open
+        hub.handle(2, ClientMessage::Reconnect { code: code.clone(), token }, ABANDONED_AFTER - 1);
+        hub.tick(3 * ABANDONED_AFTER);
+        hub.disconnect(2);
+        hub.tick(4 * ABANDONED_AFTER - 1);
+        assert!(hub.record(&code).is_some());
+        hub.tick(4 * ABANDONED_AFTER);
+        assert!(hub.record(&code).is_none());
+
+        let create = ClientMessage::Create { clock: None };
+        for _ in 0..MAX_LOBBIES_PER_CLIENT {
+            joined(&hub.handle(3, create.clone(), 0));
+        }
+        assert!(matches!(hub.handle(3, create, 0)[0].1, ServerMessage::Error { .. }));
+    }
+}
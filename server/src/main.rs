@@ -0,0 +1,16 @@
+use star_realms_server::server::serve;
+use tokio::net::TcpListener;
+
+const DEFAULT_ADDRESS: &str = "0.0.0.0:9000";
+
+#[tokio::main]
+async fn main() -> std::io::Result<()> {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    let address = match args.iter().position(|a| a == "--addr") {
+        Some(i) => args.get(i + 1).cloned().unwrap_or_else(|| DEFAULT_ADDRESS.to_string()),
+        None => DEFAULT_ADDRESS.to_string(),
+    };
+    let listener = TcpListener::bind(&address).await?;
+    println!("Listening on ws://{}", listener.local_addr()?);
+    serve(listener).await
+}
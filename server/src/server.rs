@@ -0,0 +1,87 @@
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::time::{Duration, Instant};
+use futures_util::{SinkExt, StreamExt};
+use star_realms_engine::protocol::{ClientMessage, ServerMessage};
+use tokio::net::{TcpListener, TcpStream};
+use tokio::sync::mpsc::{unbounded_channel, UnboundedSender};
+use tokio_tungstenite::tungstenite::Message;
+use crate::lobby::{ClientId, Hub, Millis, Outbox};
+
+type Senders = Arc<Mutex<HashMap<ClientId, UnboundedSender<ServerMessage>>>>;
+
+/// How often the clocks are checked for players who ran out of time
+const TICK: Duration = Duration::from_millis(250);
+
+fn millis(start: Instant) -> Millis {
+    start.elapsed().as_millis() as Millis
+}
+
+fn lock<T>(shared: &Mutex<T>) -> MutexGuard<'_, T> {
+    shared.lock().expect("No task panics while holding a lock")
+}
+
+fn deliver(senders: &Senders, outbox: Outbox) {
+    let senders = lock(senders);
+    for (client, message) in outbox {
+        if let Some(sender) = senders.get(&client) {
+            // a closed channel means the client is leaving, `connection` cleans up
+            let _ = sender.send(message);
+        }
+    }
+}
+
+/// Accepts WebSocket clients forever
+pub async fn serve(listener: TcpListener) -> std::io::Result<()> {
+    let hub = Arc::new(Mutex::new(Hub::new(rand::random())));
+    let senders: Senders = Arc::new(Mutex::new(HashMap::new()));
+    let start = Instant::now();
+    let (clock_hub, clock_senders) = (hub.clone(), senders.clone());
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(TICK);
+        loop {
+            interval.tick().await;
+            let outbox = lock(&clock_hub).tick(millis(start));
+            deliver(&clock_senders, outbox);
+        }
+    });
+    let mut next_client = 0;
+    loop {
+        let (stream, _) = listener.accept().await?;
+        next_client += 1;
+        tokio::spawn(connection(stream, next_client, start, hub.clone(), senders.clone()));
+    }
+}
+
+async fn connection(stream: TcpStream, client: ClientId, start: Instant, hub: Arc<Mutex<Hub>>, senders: Senders) {
+    let socket = match tokio_tungstenite::accept_async(stream).await {
+        Ok(socket) => socket,
+        Err(_) => return,
+    };
+    let (mut sink, mut incoming) = socket.split();
+    let (sender, mut outgoing) = unbounded_channel::<ServerMessage>();
+    lock(&senders).insert(client, sender);
+    let writer = tokio::spawn(async move {
+        while let Some(message) = outgoing.recv().await {
+            let text = serde_json::to_string(&message).expect("Server messages are serializable");
+            if sink.send(Message::Text(text)).await.is_err() {
+                break;
+            }
+        }
+    });
+    while let Some(Ok(message)) = incoming.next().await {
+        let outbox = match message {
+            Message::Text(text) => match serde_json::from_str::<ClientMessage>(&text) {
+                Ok(message) => lock(&hub).handle(client, message, millis(start)),
+                Err(e) => vec![(client, ServerMessage::Error { message: format!("Bad message: {e}") })],
+            },
+            Message::Close(_) => break,
+            _ => continue,
+        };
+        deliver(&senders, outbox);
+    }
+    lock(&senders).remove(&client);
+    let outbox = lock(&hub).disconnect(client);
+    deliver(&senders, outbox);
+    writer.abort();
+}
@@ -0,0 +1,82 @@
+use futures_util::{SinkExt, StreamExt};
+use star_realms_engine::gamelogic::Action;
+use star_realms_engine::protocol::{ClientMessage, ServerMessage};
+use star_realms_server::server::serve;
+use tokio::net::{TcpListener, TcpStream};
+use tokio_tungstenite::tungstenite::Message;
+use tokio_tungstenite::{connect_async, MaybeTlsStream, WebSocketStream};
+
+struct Client(WebSocketStream<MaybeTlsStream<TcpStream>>);
+
+impl Client {
+    async fn connect(address: &str) -> Client {
+        Client(connect_async(format!("ws://{address}")).await.unwrap().0)
+    }
+
+    async fn send(&mut self, message: ClientMessage) {
+        self.0.send(Message::Text(serde_json::to_string(&message).unwrap())).await.unwrap();
+    }
+
+    async fn receive(&mut self) -> ServerMessage {
+        loop {
+            if let Message::Text(text) = self.0.next().await.unwrap().unwrap() {
+                return serde_json::from_str(&text).unwrap();
+            }
+        }
+    }
+
+    async fn joined(&mut self) -> (String, Option<i32>, Option<String>) {
+        match self.receive().await {
+            ServerMessage::Joined { code, seat, token } => (code, seat, token),
+            m => panic!("Expected joined, got {m:?}"),
+        }
+    }
+
+    /// The next state: turn, and whether the hand of each player is visible
+    async fn state(&mut self) -> (i32, [bool; 2]) {
+        match self.receive().await {
+            ServerMessage::State { view } => (view.turn, [view.players[0].hand.is_some(), view.players[1].hand.is_some()]),
+            m => panic!("Expected a state, got {m:?}"),
+        }
+    }
+}
+
+#[tokio::test]
+async fn test_lobby_over_websocket() {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let address = listener.local_addr().unwrap().to_string();
+    tokio::spawn(serve(listener));
+
+    let mut first = Client::connect(&address).await;
+    first.send(ClientMessage::Create { clock: None }).await;
+    let (code, seat, token) = first.joined().await;
+    assert_eq!(seat, Some(0));
+    assert_eq!(first.state().await, (0, [true, false]));
+
+    let mut second = Client::connect(&address).await;
+    second.send(ClientMessage::Join { code: code.clone() }).await;
+    assert_eq!(second.joined().await.1, Some(1));
+    assert_eq!(second.state().await, (0, [false, true]));
+    assert_eq!(first.state().await, (0, [true, false]));
+
+    let mut spectator = Client::connect(&address).await;
+    spectator.send(ClientMessage::Spectate { code: code.clone() }).await;
+    assert_eq!(spectator.joined().await.1, None);
+    assert_eq!(spectator.state().await, (0, [false, false]));
+
+    second.send(ClientMessage::Action { action: Action::EndTurn }).await;
+    assert!(matches!(second.receive().await, ServerMessage::Error { .. }));
+    first.send(ClientMessage::Action { action: Action::EndTurn }).await;
+    assert_eq!(first.state().await.0, 1);
+    assert_eq!(second.state().await.0, 1);
+    assert_eq!(spectator.state().await.0, 1);
+
+    drop(first);
+    assert_eq!(second.receive().await, ServerMessage::Presence { seat: 0, connected: false });
+    assert_eq!(spectator.receive().await, ServerMessage::Presence { seat: 0, connected: false });
+    let mut back = Client::connect(&address).await;
+    back.send(ClientMessage::Reconnect { code, token: token.unwrap() }).await;
+    assert_eq!(back.joined().await.1, Some(0));
+    assert_eq!(back.state().await, (1, [true, false]));
+    assert_eq!(second.receive().await, ServerMessage::Presence { seat: 0, connected: true });
+}
@@ -0,0 +1,62 @@
+use gloo_worker::{HandlerId, Worker, WorkerScope};
+use serde::{Deserialize, Serialize};
+use star_realms_engine::agents::{Agent, MonteCarloAgent};
+use star_realms_engine::gamelogic::GameLogic;
+use star_realms_engine::replay::{replay, Replay};
+
+/// Upper bound on the actions of one computer turn, in case the agent never ends it
+const MAX_TURN_ACTIONS: usize = 200;
+
+/// Asks for a whole turn of the computer player.
+///
+/// The game travels as replay text since `GameState` cannot be serialized.
+#[derive(Serialize, Deserialize)]
+pub struct AiRequest {
+    /// Sent back with the answer, also seeds the search
+    pub id: u64,
+    pub replay: String,
+    /// Search budget of each decision
+    pub playouts: usize,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct AiResponse {
+    pub id: u64,
+    /// The actions of the turn in their text form, or why they could not be found
+    pub actions: Result<Vec<String>, String>,
+}
+
+/// Runs `MonteCarloAgent` away from the UI thread
+pub struct AiWorker;
+
+impl AiWorker {
+    fn play_turn(request: &AiRequest) -> Result<Vec<String>, String> {
+        let record: Replay = request.replay.parse()?;
+        let mut gs = replay(&record)?.pop().ok_or("Empty replay")?;
+        let turn = gs.turn;
+        let mut agent = MonteCarloAgent::new(request.id, request.playouts);
+        let mut actions = vec![];
+        while gs.turn == turn && !gs.is_over() && actions.len() < MAX_TURN_ACTIONS {
+            let action = agent.act(&gs);
+            gs = GameLogic::apply(&gs, &action)?;
+            actions.push(action.to_string());
+        }
+        Ok(actions)
+    }
+}
+
+impl Worker for AiWorker {
+    type Message = ();
+    type Input = AiRequest;
+    type Output = AiResponse;
+
+    fn create(_scope: &WorkerScope<Self>) -> Self {
+        AiWorker
+    }
+
+    fn update(&mut self, _scope: &WorkerScope<Self>, _msg: Self::Message) {}
+
+    fn received(&mut self, scope: &WorkerScope<Self>, msg: Self::Input, id: HandlerId) {
+        scope.respond(id, AiResponse { id: msg.id, actions: Self::play_turn(&msg) });
+    }
+}
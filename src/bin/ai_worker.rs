@@ -0,0 +1,6 @@
+use gloo_worker::Registrable;
+use star_realms::ai_worker::AiWorker;
+
+fn main() {
+    AiWorker::registrar().register();
+}
@@ -0,0 +1,435 @@
+use std::io::{self, BufRead, Write};
+use rand::{thread_rng, Rng};
+use star_realms_engine::agents::{self, Agent, AGENT_NAMES};
+use star_realms_engine::cards::{Card, Faction};
+use star_realms_engine::events::Event;
+use star_realms_engine::gamelogic::{Action, GameLogic, Target};
+use star_realms_engine::history::History;
+use star_realms_engine::purchases::{PurchaseConfig, PurchaseStudy};
+use star_realms_engine::replay::{replay, Replay};
+use star_realms_engine::star_realms::GameState;
+use star_realms_engine::tournament::{Entrant, Tournament, TournamentConfig};
+
+const USAGE: &str = "usage:
+  cli play [--bot NAME] [--seed N] [--second] [--save FILE] [--from FILE [--turn N]]
+                                                 play against a bot, or resume a recorded game
+  cli bots [--p1 NAME] [--p2 NAME] [--seed N] [--save FILE]
+                                                 watch two bots play
+  cli replay FILE [--turn N]                     show a recorded game
+  cli simulate [--agents A,B,..] [--games N] [--seed N] [--threads N]
+                                                 run a tournament between bots
+  cli purchases [--agents A,B,..] [--games N] [--seed N] [--threads N] [--log FILE]
+                                                 rank cards by their value in bot games";
+
+const HELP: &str = "commands (indices start at 1):
+  play N | buy N | buy explorer | ally N | scrap [N] | use N
+  attack | attack base N | end
+  choose N | select hand|discard|row|played|base N | confirm | decline
+  undo | redo | board | help | quit";
+
+fn faction_tag(card: &Card) -> &'static str {
+    match card.get_faction() {
+        Some(Faction::Blob) => "BL",
+        Some(Faction::Machine) => "MA",
+        Some(Faction::Star) => "ST",
+        Some(Faction::Trade) => "TR",
+        None => "--",
+    }
+}
+
+fn card_line(card: &Card) -> String {
+    let mut line = format!("{} {}", faction_tag(card), card.get_name());
+    if let Some(cost) = card.get_cost() {
+        line += &format!(" ({cost})");
+    }
+    if let Some(defense) = card.get_defense() {
+        line += &format!(" [{defense}{}]", if card.is_outpost() { " outpost" } else { "" });
+    }
+    line
+}
+
+fn numbered(cards: &[Card]) -> String {
+    if cards.is_empty() {
+        return "-".to_string();
+    }
+    cards.iter()
+        .enumerate()
+        .map(|(i, c)| format!("[{}] {}", i + 1, card_line(c)))
+        .collect::<Vec<String>>()
+        .join("  ")
+}
+
+/// Text view of the board as seen by `viewer`: the opponent's hand stays hidden
+fn render(gs: &GameState, viewer: i32) -> String {
+    let (Ok(me), Ok(opponent)) = (gs.get_player(viewer), gs.get_player(1 - viewer)) else {
+        return format!("There is no player {}\n", viewer + 1);
+    };
+    let bases: Vec<Card> = opponent.hand.get_played_bases().into_iter().cloned().collect();
+    let mut out = String::new();
+    out += &format!("=== turn {} - player {} to play ===\n", gs.turn + 1, gs.turn % 2 + 1);
+    out += &format!("Player {}: authority {}, deck {}, discard {}, hand {} cards\n",
+                    2 - viewer, opponent.authority, opponent.deck.len(), opponent.discard.len(), opponent.hand.playable.len());
+    out += &format!("  bases: {}\n", numbered(&bases));
+    if viewer != gs.turn % 2 {
+        out += &format!("  played: {}\n", numbered(&opponent.hand.played));
+    }
+    out += &format!("Trade row: {}\n", numbered(&gs.trade_row));
+    out += &format!("Explorers: {}, trade deck: {}, scrap heap: {}\n", gs.explorers.len(), gs.trade_deck.len(), gs.scrap.len());
+    out += &format!("Player {}: authority {}, trade {}, damages {}, deck {}, discard {}\n",
+                    viewer + 1, me.authority, me.hand.trade, me.hand.damage, me.deck.len(), me.discard.len());
+    out += &format!("  played: {}\n", numbered(&me.hand.played));
+    out += &format!("  hand: {}\n", numbered(&me.hand.playable));
+    if !me.hand.abilities.is_empty() {
+        let abilities: Vec<String> = me.hand.abilities.iter().enumerate().map(|(i, a)| format!("[{}] {}", i + 1, a)).collect();
+        out += &format!("  abilities: {}\n", abilities.join("  "));
+    }
+    if let Some(decision) = gs.pending.first() {
+        out += &format!("Decision: {}\n", decision.ability);
+    }
+    out
+}
+
+fn describe(gs: &GameState, action: &Action) -> String {
+    let me = gs.current_player();
+    let name = |c: Option<&Card>| c.map_or("?".to_string(), |c| c.get_name());
+    match *action {
+        Action::Play(i) => format!("plays {}", name(me.hand.playable.get(i))),
+        Action::Buy(i) => format!("buys {}", name(gs.trade_row.get(i))),
+        Action::BuyExplorer => "buys an Explorer".to_string(),
+        Action::Ally(i) => format!("uses the ally ability of {}", name(me.hand.played.get(i))),
+        Action::Scrap(i) => format!("scraps {}", name(me.hand.played.get(i))),
+        Action::Activate(i) => format!("uses {}", me.hand.abilities.get(i).map_or("?".to_string(), |a| a.to_string())),
+        Action::AttackPlayer => format!("attacks for {}", me.hand.damage),
+        Action::AttackBase(_) => "attacks a base".to_string(),
+        Action::Choose(i) => format!("chooses option {}", i + 1),
+        Action::Select(t) => format!("selects {}", name(GameLogic::target_card(gs, &t).as_ref())),
+        Action::Confirm => "confirms".to_string(),
+        Action::Decline => "declines".to_string(),
+        Action::EndTurn => "ends the turn".to_string(),
+        Action::Timeout => "runs out of time".to_string(),
+    }
+}
+
+/// Game log of an action of `who`: what happened, or what was done when nothing visible happened.
+/// The winner is announced separately.
+fn log(who: &str, gs: &GameState, action: &Action, events: &[Event]) {
+    if events.is_empty() {
+        println!("{who} {}", describe(gs, action));
+    }
+    for event in events.iter().filter(|e| !matches!(e, Event::GameWon { .. })) {
+        println!("{event}");
+    }
+}
+
+fn index(word: Option<&str>) -> Result<usize, String> {
+    let word = word.ok_or("missing number")?;
+    match word.parse::<usize>() {
+        Ok(n) if n > 0 => Ok(n - 1),
+        _ => Err(format!("not a card number: {word}")),
+    }
+}
+
+fn parse(gs: &GameState, input: &str) -> Result<Action, String> {
+    let mut words = input.split_whitespace();
+    let command = words.next().ok_or("empty command")?;
+    let action = match command {
+        "play" | "p" => Action::Play(index(words.next())?),
+        "buy" | "b" => match words.next() {
+            Some("explorer") | Some("e") => Action::BuyExplorer,
+            n => Action::Buy(index(n)?),
+        },
+        "ally" => Action::Ally(index(words.next())?),
+        "scrap" => match words.next() {
+            // Without a number, scrap the only card that can be
+            None => {
+                let scraps: Vec<Action> = GameLogic::legal_actions(gs).into_iter().filter(|a| matches!(a, Action::Scrap(_))).collect();
+                match scraps[..] {
+                    [a] => a,
+                    [] => return Err("nothing to scrap".to_string()),
+                    _ => return Err("several cards can be scrapped, give its number".to_string()),
+                }
+            }
+            n => Action::Scrap(index(n)?),
+        },
+        "use" => Action::Activate(index(words.next())?),
+        "attack" | "a" => match words.next() {
+            None | Some("player") => Action::AttackPlayer,
+            Some("base") => Action::AttackBase(index(words.next())?),
+            Some(w) => return Err(format!("cannot attack {w}")),
+        },
+        "choose" => Action::Choose(index(words.next())?),
+        "select" | "s" => {
+            let zone = words.next().ok_or("missing zone")?;
+            let i = index(words.next())?;
+            Action::Select(match zone {
+                "hand" => Target::Playable(i),
+                "discard" => Target::Discarded(i),
+                "row" => Target::TradeRow(i),
+                "played" => Target::Played(i),
+                "base" => Target::EnemyBase(i),
+                z => return Err(format!("unknown zone {z}")),
+            })
+        }
+        "confirm" => Action::Confirm,
+        "decline" => Action::Decline,
+        "end" | "e" => Action::EndTurn,
+        c => return Err(format!("unknown command {c}")),
+    };
+    match words.next() {
+        None => Ok(action),
+        Some(w) => Err(format!("unexpected {w}")),
+    }
+}
+
+/// Value following `--name` in the arguments
+fn option<'a>(args: &'a [String], name: &str) -> Option<&'a str> {
+    args.iter().position(|a| a == name).and_then(|i| args.get(i + 1)).map(|s| s.as_str())
+}
+
+fn numeric_option(args: &[String], name: &str, default: u64) -> Result<u64, String> {
+    match option(args, name) {
+        None => Ok(default),
+        Some(v) => v.parse().map_err(|_| format!("{name} expects a number")),
+    }
+}
+
+fn agent(name: &str, seed: u64) -> Result<Box<dyn Agent>, String> {
+    agents::from_name(name, seed).ok_or(format!("unknown agent {name}, expected one of {}", AGENT_NAMES.join(", ")))
+}
+
+fn load(path: &str) -> Result<Replay, String> {
+    std::fs::read_to_string(path).map_err(|e| format!("{path}: {e}"))?.parse()
+}
+
+/// Writes the record to the `--save` file, if any
+fn save(args: &[String], record: &Replay) -> Result<(), String> {
+    match option(args, "--save") {
+        Some(path) => std::fs::write(path, record.to_string()).map_err(|e| format!("{path}: {e}")),
+        None => Ok(()),
+    }
+}
+
+/// Recorded game to start from: a new one, or the `--from` file stopped at the start of `--turn`
+fn start(args: &[String], players: [String; 2]) -> Result<(Replay, GameState), String> {
+    let record = match option(args, "--from") {
+        None => Replay::new(numeric_option(args, "--seed", thread_rng().gen())?, players),
+        Some(path) => {
+            let record = load(path)?;
+            let steps = match option(args, "--turn") {
+                None => record.steps.len(),
+                Some(turn) => {
+                    let turn = turn.parse::<i32>().map_err(|_| "--turn expects a number")?;
+                    record.turn_start(turn - 1).ok_or(format!("the game has no turn {turn}"))?
+                }
+            };
+            Replay { players, ..record.truncated(steps) }
+        }
+    };
+    let gs = replay(&record)?.pop().unwrap_or_else(|| record.initial_state());
+    Ok((record, gs))
+}
+
+/// Asks a yes or no question, no being the default
+fn confirm(lines: &mut impl Iterator<Item = io::Result<String>>, question: &str) -> Result<bool, String> {
+    print!("{question} [y/N] ");
+    io::stdout().flush().map_err(|e| e.to_string())?;
+    match lines.next() {
+        Some(line) => Ok(matches!(line.map_err(|e| e.to_string())?.trim(), "y" | "yes")),
+        None => Ok(false),
+    }
+}
+
+fn play(args: &[String]) -> Result<(), String> {
+    let bot_name = option(args, "--bot").unwrap_or("greedy");
+    let human = if args.iter().any(|a| a == "--second") { 1 } else { 0 };
+    let players = if human == 0 { ["human".to_string(), bot_name.to_string()] } else { [bot_name.to_string(), "human".to_string()] };
+    let (mut record, mut gs) = start(args, players)?;
+    let mut bot = agent(bot_name, record.seed)?;
+    let mut history = History::new();
+    println!("seed {}, you are player {}\n{HELP}\n", record.seed, human + 1);
+    let stdin = io::stdin();
+    let mut lines = stdin.lock().lines();
+    print!("{}", render(&gs, human));
+    while !gs.is_over() {
+        if gs.turn % 2 != human {
+            let action = bot.act(&gs);
+            let (next, events) = record.step(&gs, action)?;
+            log(bot.name(), &gs, &action, &events);
+            gs = next;
+            if gs.turn % 2 == human {
+                print!("{}", render(&gs, human));
+            }
+            continue;
+        }
+        print!("> ");
+        io::stdout().flush().map_err(|e| e.to_string())?;
+        let line = match lines.next() {
+            Some(line) => line.map_err(|e| e.to_string())?,
+            None => return save(args, &record),
+        };
+        match line.trim() {
+            "" => continue,
+            "quit" | "q" => return save(args, &record),
+            "help" | "h" => println!("{HELP}"),
+            "board" => print!("{}", render(&gs, human)),
+            "undo" | "u" => match history.undo(&gs) {
+                Some((action, before)) => {
+                    record.steps.pop();
+                    gs = before;
+                    println!("undone: {}", describe(&gs, &action));
+                    print!("{}", render(&gs, human));
+                }
+                None => println!("nothing to undo since cards were last revealed"),
+            },
+            "redo" => match history.redo(&gs) {
+                Some((action, _)) => {
+                    println!("redone: {}", describe(&gs, &action));
+                    gs = record.apply(&gs, action)?;
+                    print!("{}", render(&gs, human));
+                }
+                None => println!("nothing to redo"),
+            },
+            input => match parse(&gs, input) {
+                Ok(action) if history.needs_confirmation(&gs, &action)
+                    && !confirm(&mut lines, "this reveals cards, the turn cannot be undone past it. continue?")? => {}
+                Ok(action) => match record.step(&gs, action) {
+                    Ok((next, events)) => {
+                        log("you", &gs, &action, &events);
+                        history.record(action, &gs, &next);
+                        gs = next;
+                        print!("{}", render(&gs, human));
+                    }
+                    Err(e) => println!("{e}"),
+                },
+                Err(e) => println!("{e}"),
+            },
+        }
+    }
+    println!("player {} wins", gs.winner().unwrap_or(0) + 1);
+    save(args, &record)
+}
+
+fn bots(args: &[String]) -> Result<(), String> {
+    let names = [option(args, "--p1").unwrap_or("greedy"), option(args, "--p2").unwrap_or("greedy")];
+    let seed = numeric_option(args, "--seed", thread_rng().gen())?;
+    let mut players = [agent(names[0], seed)?, agent(names[1], seed.wrapping_add(1))?];
+    let mut record = Replay::new(seed, names.map(|n| n.to_string()));
+    let mut gs = record.initial_state();
+    println!("seed {seed}");
+    while !gs.is_over() {
+        let player = gs.turn % 2;
+        let action = players[player as usize].act(&gs);
+        let (next, events) = record.step(&gs, action)?;
+        log(&format!("player {} ({})", player + 1, players[player as usize].name()), &gs, &action, &events);
+        gs = next;
+        if action == Action::EndTurn {
+            print!("{}", render(&gs, gs.turn % 2));
+        }
+    }
+    println!("player {} wins after {} turns", gs.winner().unwrap_or(0) + 1, gs.turn + 1);
+    save(args, &record)
+}
+
+fn show_replay(args: &[String]) -> Result<(), String> {
+    let record = load(args.first().ok_or(USAGE)?)?;
+    let states = replay(&record)?;
+    let only_turn = match option(args, "--turn") {
+        None => None,
+        Some(turn) => Some(turn.parse::<i32>().map_err(|_| "--turn expects a number")? - 1),
+    };
+    println!("seed {}, {} against {}", record.seed, record.players[0], record.players[1]);
+    for (step, gs) in record.steps.iter().zip(states.iter()) {
+        if only_turn.is_some_and(|t| t != gs.turn) {
+            continue;
+        }
+        let player = gs.turn % 2;
+        let events = GameLogic::step(&GameLogic::spend_time(gs, step.elapsed_ms.unwrap_or(0)), &step.action)
+            .map_or(vec![], |(_, events)| events);
+        log(&format!("player {} ({})", player + 1, record.players[player as usize]), gs, &step.action, &events);
+        if step.action == Action::EndTurn || only_turn.is_some() {
+            print!("{}", render(gs, player));
+        }
+    }
+    if let Some(winner) = states.last().and_then(|gs| gs.winner()) {
+        println!("player {} wins", winner + 1);
+    }
+    Ok(())
+}
+
+/// Baseline agents given by `--agents`
+fn entrants(args: &[String]) -> Result<Vec<Entrant>, String> {
+    let names: Vec<String> = option(args, "--agents").unwrap_or("greedy,random").split(',').map(|s| s.to_string()).collect();
+    let mut entrants = vec![];
+    for name in names {
+        agent(&name, 0)?;
+        let factory_name = name.clone();
+        entrants.push(Entrant::new(&name, Box::new(move |seed| agents::from_name(&factory_name, seed).unwrap())));
+    }
+    Ok(entrants)
+}
+
+fn simulate(args: &[String]) -> Result<(), String> {
+    let entrants = entrants(args)?;
+    let config = TournamentConfig {
+        games: numeric_option(args, "--games", 100)? as usize,
+        seed: numeric_option(args, "--seed", 0)?,
+        threads: numeric_option(args, "--threads", 0)? as usize,
+        ..TournamentConfig::default()
+    };
+    println!("{}", Tournament::new(entrants, config).run());
+    Ok(())
+}
+
+fn purchases(args: &[String]) -> Result<(), String> {
+    let entrants = entrants(args)?;
+    let config = PurchaseConfig {
+        games: numeric_option(args, "--games", 1000)? as usize,
+        seed: numeric_option(args, "--seed", 0)?,
+        threads: numeric_option(args, "--threads", 0)? as usize,
+        ..PurchaseConfig::default()
+    };
+    let report = PurchaseStudy::new(entrants, config).run();
+    if let Some(path) = option(args, "--log") {
+        let mut file = std::fs::File::create(path).map_err(|e| format!("{path}: {e}"))?;
+        report.write_log(&mut file).map_err(|e| format!("{path}: {e}"))?;
+    }
+    println!("{report}");
+    Ok(())
+}
+
+fn main() {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    let result = match args.first().map(|s| s.as_str()) {
+        Some("play") => play(&args[1..]),
+        Some("bots") => bots(&args[1..]),
+        Some("replay") => show_replay(&args[1..]),
+        Some("simulate") => simulate(&args[1..]),
+        Some("purchases") => purchases(&args[1..]),
+        _ => Err(USAGE.to_string()),
+    };
+    if let Err(e) = result {
+        eprintln!("{e}");
+        std::process::exit(1);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse() {
+        let gs = GameState::with_seed(0);
+        let parse = |input| parse(&gs, input);
+        assert_eq!(parse("play 2"), Ok(Action::Play(1)));
+        assert_eq!(parse("buy 4"), Ok(Action::Buy(3)));
+        assert_eq!(parse("buy explorer"), Ok(Action::BuyExplorer));
+        assert_eq!(parse("attack base 1"), Ok(Action::AttackBase(0)));
+        assert_eq!(parse("attack"), Ok(Action::AttackPlayer));
+        assert_eq!(parse("select discard 3"), Ok(Action::Select(Target::Discarded(2))));
+        assert_eq!(parse("end"), Ok(Action::EndTurn));
+        assert!(parse("play 0").is_err());
+        assert_eq!(parse("scrap 1"), Ok(Action::Scrap(0)));
+        assert!(parse("scrap").is_err());
+    }
+}
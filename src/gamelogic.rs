@@ -1,4 +0,0 @@
-struct GameLogic {}
-impl GameLogic {
-
-}
\ No newline at end of file
@@ -0,0 +1,4 @@
+//! Code shared by the binaries of the frontends
+
+#[cfg(feature = "web")]
+pub mod ai_worker;
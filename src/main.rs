@@ -1,19 +1,195 @@
-use log::info;
+use std::rc::Rc;
+use gloo_timers::callback::Timeout;
+use gloo_worker::Spawnable;
+use web_sys::{HtmlInputElement, HtmlSelectElement, HtmlTextAreaElement};
 use yew::prelude::*;
 use yew::{classes, html};
-use crate::cards::{Card, Faction};
-use crate::star_realms::GameState;
+use star_realms::ai_worker::{AiRequest, AiResponse, AiWorker};
+use star_realms_engine::abilities::{Ability, Effect};
+use star_realms_engine::cards::{Card, Faction};
+use star_realms_engine::events;
+use star_realms_engine::gamelogic::{Action, GameLogic, Target};
+use star_realms_engine::history::History;
+use star_realms_engine::replay::{replay, Replay};
+use star_realms_engine::star_realms::GameState;
+use star_realms_engine::view::{PendingView, TableView};
 
-mod star_realms;
-mod abilities;
-mod cards;
-mod gamelogic;
+mod online;
 
 // Structures de données
 
+/// Pause between two actions of the computer, so the human can follow them
+const PLAYBACK_DELAY_MS: u32 = 700;
+
+/// The game being played. Every change goes through the engine, a refused action only sets `error`.
+struct GameModel {
+    state: Rc<GameState>,
+    /// Every action since the start, the computer player rebuilds the game from it
+    record: Replay,
+    /// Actions of the turn that can be taken back
+    history: History,
+    /// An action that would end the undo history, waiting for the player to confirm it
+    unconfirmed: Option<Action>,
+    /// Everything that happened since the game was started or loaded
+    log: Rc<Vec<events::Event>>,
+    /// Length of the log before each action of `history` that can be undone
+    log_marks: Vec<usize>,
+    error: Option<String>,
+    /// Bumped by every message, for effects that wait for the game to change
+    version: usize,
+}
+
+enum GameMsg {
+    Apply(Action),
+    /// An action of a human player, who confirms it first if it cannot be undone
+    Request(Action),
+    /// Drops the action waiting for a confirmation
+    Cancel,
+    Undo,
+    Redo,
+    /// Restarts from a recorded game, at its last step
+    Load(Replay),
+    Fail(String),
+}
+
+impl GameModel {
+    fn new(record: Replay) -> GameModel {
+        GameModel {
+            state: Rc::new(record.initial_state()),
+            record,
+            history: History::new(),
+            unconfirmed: None,
+            log: Rc::new(vec![]),
+            log_marks: vec![],
+            error: None,
+            version: 0,
+        }
+    }
+}
+
+impl Reducible for GameModel {
+    type Action = GameMsg;
+
+    fn reduce(self: Rc<Self>, msg: Self::Action) -> Rc<Self> {
+        let mut model = GameModel {
+            state: self.state.clone(),
+            record: self.record.clone(),
+            history: self.history.clone(),
+            unconfirmed: None,
+            log: self.log.clone(),
+            log_marks: self.log_marks.clone(),
+            error: None,
+            version: self.version + 1,
+        };
+        match msg {
+            GameMsg::Request(action) if model.history.needs_confirmation(&model.state, &action) => {
+                model.unconfirmed = Some(action);
+            }
+            GameMsg::Apply(action) | GameMsg::Request(action) => match model.record.step(&model.state, action) {
+                Ok((state, mut events)) => {
+                    model.history.record(action, &model.state, &state);
+                    if model.history.can_undo() {
+                        model.log_marks.push(model.log.len());
+                    } else {
+                        model.log_marks.clear();
+                    }
+                    model.state = Rc::new(state);
+                    Rc::make_mut(&mut model.log).append(&mut events);
+                }
+                Err(e) => model.error = Some(e.to_string()),
+            },
+            GameMsg::Cancel => {}
+            GameMsg::Undo => {
+                if let Some((_, before)) = model.history.undo(&model.state) {
+                    model.record.steps.pop();
+                    model.state = Rc::new(before);
+                    if let Some(mark) = model.log_marks.pop() {
+                        Rc::make_mut(&mut model.log).truncate(mark);
+                    }
+                }
+            }
+            GameMsg::Redo => {
+                if let Some((action, _)) = model.history.redo(&model.state) {
+                    match model.record.step(&model.state, action) {
+                        Ok((state, mut events)) => {
+                            model.log_marks.push(model.log.len());
+                            model.state = Rc::new(state);
+                            Rc::make_mut(&mut model.log).append(&mut events);
+                        }
+                        Err(e) => model.error = Some(e.to_string()),
+                    }
+                }
+            }
+            GameMsg::Load(record) => match replay(&record) {
+                Ok(mut states) => {
+                    model.state = Rc::new(states.pop().unwrap());
+                    model.record = record;
+                    model.history = History::new();
+                    model.log = Rc::new(vec![]);
+                    model.log_marks = vec![];
+                }
+                Err(e) => model.error = Some(e),
+            },
+            GameMsg::Fail(e) => model.error = Some(e),
+        }
+        Rc::new(model)
+    }
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum Difficulty {
+    Easy,
+    Normal,
+    Hard,
+}
+
+impl Difficulty {
+    /// Playouts the computer spends on each decision
+    fn playouts(self) -> usize {
+        match self {
+            Difficulty::Easy => 20,
+            Difficulty::Normal => 100,
+            Difficulty::Hard => 500,
+        }
+    }
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum Mode {
+    /// Two humans sharing the device
+    HotSeat,
+    /// The human plays first against the computer
+    Computer(Difficulty),
+    /// On a game server, see `online`
+    Online,
+}
+
+impl Mode {
+    const ALL: [(Mode, &'static str); 5] = [
+        (Mode::HotSeat, "Hot seat"),
+        (Mode::Computer(Difficulty::Easy), "vs. Computer (easy)"),
+        (Mode::Computer(Difficulty::Normal), "vs. Computer (normal)"),
+        (Mode::Computer(Difficulty::Hard), "vs. Computer (hard)"),
+        (Mode::Online, "Online"),
+    ];
+
+    fn players(self) -> [String; 2] {
+        match self {
+            Mode::HotSeat | Mode::Online => ["human".to_string(), "human".to_string()],
+            Mode::Computer(_) => ["human".to_string(), "montecarlo".to_string()],
+        }
+    }
+
+    fn new_game(self) -> Replay {
+        Replay::new(rand::random(), self.players())
+    }
+}
+
 #[derive(Properties, PartialEq)]
 pub struct CardProps {
     pub card: Card,
+    #[prop_or_default]
+    pub onclick: Option<Callback<MouseEvent>>,
 }
 
 #[derive(Properties, PartialEq)]
@@ -24,31 +200,78 @@ struct FactionProps {
 #[derive(Properties, PartialEq)]
 struct RowProps {
     cards: Vec<Card>,
+    /// Called with the index of the clicked card
+    #[prop_or_default]
+    on_click: Option<Callback<usize>>,
 }
 
 #[derive(Properties, PartialEq)]
 struct PlayedCardsProps {
-    bases: Vec<Card>,
-    ships: Vec<Card>,
+    played: Vec<Card>,
+    /// Scrap and ally buttons for the active player, attack buttons on the opponent's bases
+    #[prop_or_default]
+    on_action: Option<Callback<Action>>,
+    active: bool,
 }
 
+#[derive(Properties, PartialEq)]
+struct HandProps {
+    cards: Vec<Card>,
+    /// Texts of the delayed abilities waiting to be used
+    abilities: Vec<String>,
+    /// Missing for a hand that is only looked at
+    on_action: Option<Callback<Action>>,
+}
+
+#[derive(Properties, PartialEq)]
+struct HiddenHandProps {
+    count: usize,
+}
+
+#[derive(Properties, PartialEq)]
+struct GameLogProps {
+    events: Rc<Vec<events::Event>>,
+}
+
+#[derive(Properties, PartialEq)]
+struct PassDeviceProps {
+    player: i32,
+    on_ready: Callback<()>,
+}
+
+
 #[derive(Properties, PartialEq)]
 struct PlayerDiscardProps {
-    discard: Vec<Card>
+    discard: Vec<Card>,
+    #[prop_or_default]
+    on_inspect: Option<Callback<MouseEvent>>,
 }
 
 #[derive(Properties, PartialEq)]
 struct CardDeckProps {
     deck: Vec<Card>,
     visible: bool,
+    #[prop_or_default]
+    onclick: Option<Callback<MouseEvent>>,
+    /// Only for public piles, the others just show their size
+    #[prop_or_default]
+    on_inspect: Option<Callback<MouseEvent>>,
 }
 
 #[derive(Properties, PartialEq)]
 struct AuthorityProps {
     authority: i32,
-    p1: bool,
+    id: i32,
     damages: i32,
-    trade: i32
+    trade: i32,
+    /// Time left, in games with a clock
+    #[prop_or_default]
+    clock_ms: Option<u64>,
+    #[prop_or_default]
+    clock_running: bool,
+    /// Attack and end turn controls, for the active player only
+    #[prop_or_default]
+    on_action: Option<Callback<Action>>,
 }
 
 #[derive(Properties, PartialEq)]
@@ -56,20 +279,55 @@ struct PlayerProps {
     discard: Vec<Card>,
     deck: Vec<Card>,
     played: Vec<Card>,
+    hand: Vec<Card>,
+    /// Texts of the delayed abilities waiting to be used
+    abilities: Vec<String>,
+    /// Face down cards only when false
+    hand_visible: bool,
     authority: i32,
+    /// Player index, 0 plays first
+    id: i32,
+    /// Drawn at the bottom of the board, reversed
     p1: bool,
     trade: i32,
     damages: i32,
+    active: bool,
+    #[prop_or_default]
+    clock_ms: Option<u64>,
+    #[prop_or_default]
+    on_action: Option<Callback<Action>>,
+    on_inspect: Callback<Pile>,
 }
 
 #[derive(Properties, PartialEq)]
 struct CardsModalProps {
-    cards: Vec<Card>,
+    prompt: String,
+    /// Every card the pending ability can target
+    options: Vec<(Target, Card)>,
+    /// The decision cannot be cancelled when above 0
+    min: usize,
+    max: usize,
+    on_confirm: Callback<Vec<Target>>,
+    on_cancel: Callback<()>,
+}
+
+/// A public pile opened in the inspector, with its title
+type Pile = (String, Vec<Card>);
+
+#[derive(Properties, PartialEq)]
+struct PileModalProps {
+    pile: Pile,
+    on_close: Callback<()>,
 }
 
 #[derive(Properties, PartialEq)]
-struct CardModalProps {
-    card: Card,
+struct ChoiceModalProps {
+    prompt: String,
+    branches: [String; 2],
+    on_choose: Callback<usize>,
+    /// Declines both sides when given
+    #[prop_or_default]
+    on_cancel: Option<Callback<()>>,
 }
 
 #[derive(Properties, PartialEq)]
@@ -77,7 +335,49 @@ struct BoardProps {
     explorers: Vec<Card>,
     trade_row: Vec<Card>,
     trade_deck: Vec<Card>,
-    scrap: Vec<Card>
+    scrap: Vec<Card>,
+    #[prop_or_default]
+    on_action: Option<Callback<Action>>,
+    on_inspect: Callback<Pile>,
+}
+
+#[derive(Properties)]
+struct GameViewProps {
+    state: Rc<GameState>,
+    /// Player sitting at the bottom, whose hand is shown. Both hands are shown when missing.
+    #[prop_or_default]
+    viewer: Option<i32>,
+    /// For a game played on a server: `state` only draws this view, which tells what is hidden
+    #[prop_or_default]
+    remote: Option<Rc<TableView>>,
+    /// Read-only board when missing
+    #[prop_or_default]
+    on_action: Option<Callback<Action>>,
+}
+
+impl PartialEq for GameViewProps {
+    fn eq(&self, other: &Self) -> bool {
+        Rc::ptr_eq(&self.state, &other.state) && self.viewer == other.viewer && self.remote == other.remote
+            && self.on_action == other.on_action
+    }
+}
+
+#[derive(Properties)]
+struct CoachProps {
+    state: Rc<GameState>,
+    player: i32,
+}
+
+impl PartialEq for CoachProps {
+    fn eq(&self, other: &Self) -> bool {
+        Rc::ptr_eq(&self.state, &other.state) && self.player == other.player
+    }
+}
+
+#[derive(Properties, PartialEq)]
+struct ReplayViewerProps {
+    on_resume: Callback<Replay>,
+    on_close: Callback<()>,
 }
 
 // Composants
@@ -95,14 +395,37 @@ fn FactionComponent(props: &FactionProps) -> Html {
     }
 }
 
+/// Rules text of an ability, amounts of trade, combat, authority and cards drawn as icons
+fn ability_html(ability: &Ability) -> Html {
+    match ability {
+        Ability::Atomic(a) => {
+            let icon = |kind: &'static str, title: &'static str, n: &i32| html! {
+                <span class={classes!("ability-icon", kind)} title={title}>{ *n }</span>
+            };
+            match a.effect() {
+                Effect::Trade(n) => icon("icon-trade", "Trade", n),
+                Effect::Damage(n) => icon("icon-combat", "Combat", n),
+                Effect::Authority(n) => icon("icon-authority", "Authority", n),
+                Effect::Draw(n) => icon("icon-draw", "Cards drawn", n),
+                effect => html! { <span class="effect-text">{ effect.to_string() }</span> },
+            }
+        }
+        Ability::And(a, b) => html! { <>{ ability_html(a) }{ ability_html(b) }</> },
+        Ability::Or(a, b) => html! { <>{ ability_html(a) }<span class="effect-text">{"or"}</span>{ ability_html(b) }</> },
+        Ability::Cond(cond, a) => html! { <><span class="effect-text">{ format!("If {cond}:") }</span>{ ability_html(a) }</> },
+        Ability::Delayed(a) => ability_html(a),
+    }
+}
+
 #[function_component]
 fn CardComponent(props: &CardProps) -> Html {
     let faction = props.card.get_faction();
     let cost = props.card.get_cost();
     let abilities = props.card.get_abilities();
     html! {
-        <div class={classes!(
+        <div onclick={props.onclick.clone()} class={classes!(
     "card",
+    props.onclick.as_ref().map(|_| "clickable"),
     match props.card.get_faction() {
         Some(Faction::Blob) => "blob",
         Some(Faction::Machine) => "machine",
@@ -123,19 +446,19 @@ fn CardComponent(props: &CardProps) -> Html {
             <div class={classes!("card-img")}></div>
             <div class={classes!("card-bot")}>
                 <div class={classes!("ability-container")}>
-                    if let Some(ability) = abilities.on_board {
+                    if let Some(ability) = &abilities.on_board {
                         <div class={classes!("ability", "on-play")}>
-                            <span class="ability-text">{"On Play: "} { ability.to_string() }</span>
+                            <span class="ability-text">{"On Play: "} { ability_html(ability) }</span>
                         </div>
                     }
-                    if let Some(ability) = abilities.on_faction {
+                    if let Some(ability) = &abilities.on_faction {
                         <div class={classes!("ability", "on-faction")}>
-                            <span class="ability-text">{"On Faction: "} { ability.to_string() }</span>
+                            <span class="ability-text">{"On Faction: "} { ability_html(ability) }</span>
                         </div>
                     }
-                    if let Some(ability) = abilities.on_scrap {
+                    if let Some(ability) = &abilities.on_scrap {
                         <div class={classes!("ability", "on-scrap")}>
-                            <span class="ability-text">{"On Scrap: "} { ability.to_string() }</span>
+                            <span class="ability-text">{"On Scrap: "} { ability_html(ability) }</span>
                         </div>
                     }
             </div>
@@ -149,7 +472,10 @@ fn TradeRow(props: &RowProps) -> Html {
     html! {
        <div class="trade-row-container">
             <div class="trade-row">
-                { for props.cards.iter().map(|card| html! { <div class="trade-row-elem"><CardComponent card={card.clone()} /> </div>}) }
+                { for props.cards.iter().enumerate().map(|(i, card)| {
+                    let onclick = props.on_click.clone().map(|cb| cb.reform(move |_: MouseEvent| i));
+                    html! { <div class="trade-row-elem"><CardComponent card={*card} {onclick} /> </div>}
+                }) }
             </div>
         </div>
     }
@@ -157,18 +483,157 @@ fn TradeRow(props: &RowProps) -> Html {
 
 #[function_component]
 fn PlayedCards(props: &PlayedCardsProps) -> Html {
+    // indices into `played`, the engine addresses cards in play by it
+    let (bases, ships): (Vec<_>, Vec<_>) = props.played.iter().enumerate().partition(|(_, c)| c.is_base());
+    let mut base_index = 0;
+    let card = |i: usize, card: &Card, base: Option<usize>| {
+        let buttons = match &props.on_action {
+            Some(on_action) if props.active => html! {
+                <div class={classes!("card-actions")}>
+                    if card.get_abilities().on_scrap.is_some() {
+                        <button onclick={on_action.reform(move |_: MouseEvent| Action::Scrap(i))}>{"Scrap"}</button>
+                    }
+                    if card.get_abilities().on_faction.is_some() {
+                        <button onclick={on_action.reform(move |_: MouseEvent| Action::Ally(i))}>{"Ally"}</button>
+                    }
+                </div>
+            },
+            Some(on_action) => match base {
+                Some(b) => html! {
+                    <div class={classes!("card-actions")}>
+                        <button onclick={on_action.reform(move |_: MouseEvent| Action::AttackBase(b))}>{"Attack"}</button>
+                    </div>
+                },
+                None => html! {},
+            },
+            None => html! {},
+        };
+        html! {
+            <div class={classes!("played-card")}>
+                <CardComponent card={*card} />
+                { buttons }
+            </div>
+        }
+    };
     html! {
         <div class="played-cards">
             <div class="base-cards">
-                { for props.bases.iter().map(|card| html! { <CardComponent card={card.clone()} /> }) }
+                { for bases.into_iter().map(|(i, c)| {
+                    base_index += 1;
+                    card(i, c, Some(base_index - 1))
+                }) }
             </div>
             <div class="ship-cards">
-                { for props.ships.iter().map(|card| html! { <CardComponent card={card.clone()} /> }) }
+                { for ships.into_iter().map(|(i, c)| card(i, c, None)) }
             </div>
         </div>
     }
 }
 
+#[function_component]
+fn HandCards(props: &HandProps) -> Html {
+    html! {
+        <div class={classes!("hand-cards")}>
+            { for props.cards.iter().enumerate().map(|(i, card)| {
+                let onclick = props.on_action.clone().map(|cb| cb.reform(move |_: MouseEvent| Action::Play(i)));
+                html! { <CardComponent card={*card} {onclick} /> }
+            }) }
+            { for props.abilities.iter().enumerate().map(|(i, text)| html! {
+                <button class={classes!("delayed-ability")} disabled={props.on_action.is_none()}
+                    onclick={props.on_action.clone().map(|cb| cb.reform(move |_: MouseEvent| Action::Activate(i)))}>
+                    { format!("Use: {text}") }
+                </button>
+            }) }
+        </div>
+    }
+}
+
+#[function_component]
+fn HiddenHand(props: &HiddenHandProps) -> Html {
+    html! {
+        <div class={classes!("hidden-hand")}>
+            <CardBackComponent />
+            <div class={classes!("hidden-hand-count")}>{ format!("{} in hand", props.count) }</div>
+        </div>
+    }
+}
+
+/// Number of events shown by `GameLog`
+const LOG_LENGTH: usize = 8;
+
+/// The last events of the game, the newest first. Entries are keyed by their position in the
+/// game so that only new ones play the appearing animation.
+#[function_component]
+fn GameLog(props: &GameLogProps) -> Html {
+    let first = props.events.len().saturating_sub(LOG_LENGTH);
+    html! {
+        <ul class={classes!("game-log")}>
+            { for props.events.iter().enumerate().skip(first).rev().map(|(i, event)| html! {
+                <li key={i}>{ event.to_string() }</li>
+            }) }
+        </ul>
+    }
+}
+
+/// Number of cards whose chances to be drawn are listed by the coach
+const COACH_CARDS: usize = 5;
+
+/// What the player can expect from their next hand, see `star_realms_engine::analysis`
+#[function_component]
+fn Coach(props: &CoachProps) -> Html {
+    let Ok(player) = props.state.get_player(props.player) else {
+        return html! {};
+    };
+    let analysis = player.analysis();
+    let percent = |p: f64| format!("{:.0}%", p * 100.0);
+    let factions = [Faction::Blob, Faction::Machine, Faction::Star, Faction::Trade];
+    let mut cards: Vec<Card> = player.deck.iter().chain(player.discard.iter()).chain(player.hand.playable.iter())
+        .chain(player.hand.played.iter().filter(|c| !c.is_base()))
+        .copied()
+        .collect();
+    cards.sort();
+    cards.dedup();
+    let mut likely: Vec<(Card, f64)> = cards.into_iter().map(|c| (c, analysis.card_probability(c))).collect();
+    likely.sort_by(|a, b| b.1.total_cmp(&a.1));
+    html! {
+        <div class={classes!("coach")}>
+            <h3>{ format!("Coach: next hand of player {}", props.player + 1) }</h3>
+            <p>
+                <span class={classes!("ability-icon", "icon-trade")} title="Trade">{ format!("{:.1}", analysis.expected_trade()) }</span>
+                <span class={classes!("ability-icon", "icon-combat")} title="Combat">{ format!("{:.1}", analysis.expected_damage()) }</span>
+                <span class={classes!("ability-icon", "icon-authority")} title="Authority">{ format!("{:.1}", analysis.expected_authority()) }</span>
+                { format!("expected, {} hands before the discard pile is shuffled", analysis.turns_until_reshuffle()) }
+            </p>
+            <table>
+                <tr><th>{"Faction"}</th><th>{"Cards"}</th><th>{"Allies in the hand"}</th></tr>
+                { for analysis.composition().into_iter().map(|(faction, count)| html! {
+                    <tr>
+                        <td>{ faction.as_ref().map_or("No faction".to_string(), |f| f.to_string()) }</td>
+                        <td>{ count }</td>
+                        <td>{ faction.filter(|f| factions.contains(f)).map_or(String::new(), |f| percent(analysis.ally_probability(&f))) }</td>
+                    </tr>
+                }) }
+            </table>
+            <ul>
+                { for likely.iter().take(COACH_CARDS).map(|(card, p)| html! {
+                    <li>{ format!("{}: {}", card.get_name(), percent(*p)) }</li>
+                }) }
+            </ul>
+        </div>
+    }
+}
+
+/// Covers the board between two hot-seat turns so the hands stay secret
+#[function_component]
+fn PassDevice(props: &PassDeviceProps) -> Html {
+    html! {
+        <div class={classes!("pass-device")}>
+            <p>{ format!("Pass the device to player {}", props.player + 1) }</p>
+            <button onclick={props.on_ready.reform(|_: MouseEvent| ())}>{"Ready"}</button>
+        </div>
+    }
+}
+
 #[function_component]
 fn EmptyPile() -> Html {
     html! {
@@ -181,9 +646,27 @@ fn EmptyPile() -> Html {
 
 #[function_component]
 fn PlayerDiscard(props: &PlayerDiscardProps) -> Html {
-    match props.discard.clone().pop() {
+    let top = match props.discard.last().copied() {
         None => html! { <EmptyPile /> },
-        Some(c) => html! { <CardComponent card={c.clone()} /> }
+        Some(c) => html! { <CardComponent card={c} /> }
+    };
+    html! {
+        <div class={classes!("pile")}>
+            { top }
+            { pile_info(props.discard.len(), &props.on_inspect) }
+        </div>
+    }
+}
+
+/// Size of a pile under it, and a button to look through it when it is public
+fn pile_info(size: usize, on_inspect: &Option<Callback<MouseEvent>>) -> Html {
+    html! {
+        <div class={classes!("pile-info")}>
+            <span>{ format!("{size} cards") }</span>
+            if let Some(on_inspect) = on_inspect {
+                <button onclick={on_inspect.clone()}>{"View"}</button>
+            }
+        </div>
     }
 }
 
@@ -198,20 +681,32 @@ fn CardBackComponent() -> Html {
 
 #[function_component]
 fn CardDeck(props: &CardDeckProps) -> Html {
-    let c = props.deck.clone().pop().clone();
-    match c {
+    let c = props.deck.last().copied();
+    let top = match c {
         None => html! { <EmptyPile /> },
         Some(c) => if props.visible {
-            html! { <CardComponent card={c} /> }
+            html! { <CardComponent card={c} onclick={props.onclick.clone()} /> }
         } else {
             html! { <CardBackComponent /> }
         }
+    };
+    html! {
+        <div class={classes!("pile")}>
+            { top }
+            { pile_info(props.deck.len(), &props.on_inspect) }
+        </div>
     }
 }
 
+/// `m:ss`, rounded up so that 0:00 means the time is up
+fn clock_text(ms: u64) -> String {
+    let seconds = ms.div_ceil(1000);
+    format!("{}:{:02}", seconds / 60, seconds % 60)
+}
+
 #[function_component]
 fn PlayerAuthority(props: &AuthorityProps) -> Html {
-    let player_name = if props.p1 { "Player 1"} else { "Player 2" };
+    let player_name = format!("Player {}", props.id + 1);
     html! {
             <div class={classes!("authority-card")}>
                 <div>
@@ -223,60 +718,194 @@ fn PlayerAuthority(props: &AuthorityProps) -> Html {
                     <div class={classes!("authority-icon")}>{player_name}</div>
                     <div class={classes!("authority-number")}>{props.authority}</div>
                 </div>
+                if let Some(ms) = props.clock_ms {
+                    <div class={classes!("player-clock", props.clock_running.then_some("running"))}>{ clock_text(ms) }</div>
+                }
+                if let Some(on_action) = &props.on_action {
+                    <div class={classes!("card-actions")}>
+                        <button onclick={on_action.reform(|_: MouseEvent| Action::AttackPlayer)}>{"Attack"}</button>
+                        <button onclick={on_action.reform(|_: MouseEvent| Action::EndTurn)}>{"End Turn"}</button>
+                    </div>
+                }
             </div>
     }
 }
 
 #[function_component]
 fn Player(props: &PlayerProps) -> Html {
-    let played = props.played.clone();
-    let bases = Card::filter_bases(played.clone());
-    let ships = Card::filter_ships(played);
+    let on_inspect = {
+        let (title, discard) = (format!("Player {} discard pile", props.id + 1), props.discard.clone());
+        Some(props.on_inspect.reform(move |_: MouseEvent| (title.clone(), discard.clone())))
+    };
+    let own_controls = props.on_action.clone().filter(|_| props.active);
+    let played = html! { <PlayedCards played={props.played.clone()} on_action={props.on_action.clone()} active={props.active} /> };
+    let authority = html! { <PlayerAuthority authority={props.authority} id={ props.id } trade={ props.trade } damages={ props.damages }
+        clock_ms={props.clock_ms} clock_running={props.active} on_action={own_controls.clone()} /> };
+    let hand = if props.hand_visible {
+        html! { <HandCards cards={props.hand.clone()} abilities={props.abilities.clone()} on_action={own_controls} /> }
+    } else {
+        html! {}
+    };
+    let hidden_hand = if props.hand_visible {
+        html! {}
+    } else {
+        html! { <HiddenHand count={props.hand.len()} /> }
+    };
     if !props.p1 {
         html! {
-            <div class={classes!("player-row")}>
-                <PlayerDiscard discard={props.discard.clone()} />
-                <CardDeck deck={props.deck.clone()} visible={false} />
-                <PlayedCards bases={bases.clone()} ships={ships.clone()} />
-                <PlayerAuthority authority={props.authority} p1={ props.p1 } trade={ props.trade } damages={ props.damages } />
-            </div>
+            <>
+                { hand }
+                <div class={classes!("player-row", props.active.then_some("active-player"))}>
+                    <PlayerDiscard discard={props.discard.clone()} on_inspect={on_inspect.clone()} />
+                    <CardDeck deck={props.deck.clone()} visible={false} />
+                    { hidden_hand }
+                    { played }
+                    { authority }
+                </div>
+            </>
         }
     } else {
         html! {
-            <div class={classes!("player-row-rev")}>
-                <PlayerAuthority authority={props.authority}  p1={ props.p1 } trade={ props.trade } damages={ props.damages } />
-                <PlayedCards bases={bases.clone()} ships={ships.clone()} />
-                <CardDeck deck={props.deck.clone()} visible={false} />
-                <PlayerDiscard discard={props.discard.clone()} />
-            </div>
+            <>
+                <div class={classes!("player-row-rev", props.active.then_some("active-player"))}>
+                    { authority }
+                    { played }
+                    { hidden_hand }
+                    <CardDeck deck={props.deck.clone()} visible={false} />
+                    <PlayerDiscard discard={props.discard.clone()} on_inspect={on_inspect.clone()} />
+                </div>
+                { hand }
+            </>
         }
     }
 }
 
+/// Name of the zone a target points into
+fn zone_name(target: &Target) -> &'static str {
+    match target {
+        Target::EnemyBase(_) => "Enemy bases",
+        Target::TradeRow(_) => "Trade row",
+        Target::Played(_) => "In play",
+        Target::Discarded(_) => "Discard pile",
+        Target::Playable(_) => "Hand",
+    }
+}
+
+/// Picks between `min` and `max` of the offered cards. Clicking outside hides it to look at the board.
 #[function_component]
 fn CardsModal(props: &CardsModalProps) -> Html {
-    let hideModal = Callback::from(|_| {
-       info!("test");
-    });
+    let selected = use_state(Vec::<Target>::new);
+    let hidden = use_state(|| false);
+    let hide_modal = {
+        let hidden = hidden.clone();
+        Callback::from(move |_: MouseEvent| hidden.set(true))
+    };
+    if *hidden {
+        let hidden = hidden.clone();
+        return html! {
+            <button class={classes!("modal-reopen")} onclick={Callback::from(move |_: MouseEvent| hidden.set(false))}>
+                { format!("Back to: {}", props.prompt) }
+            </button>
+        };
+    }
+
+    let mut zones: Vec<&str> = props.options.iter().map(|(t, _)| zone_name(t)).collect();
+    zones.dedup();
+    let toggle = |target: Target| {
+        let (selected, max) = (selected.clone(), props.max);
+        Callback::from(move |_: MouseEvent| {
+            let mut now = (*selected).clone();
+            match now.iter().position(|t| *t == target) {
+                Some(i) => { now.remove(i); }
+                None if now.len() < max => now.push(target),
+                None => {}
+            }
+            selected.set(now);
+        })
+    };
+    let onconfirm = {
+        let (selected, on_confirm) = (selected.clone(), props.on_confirm.clone());
+        Callback::from(move |_: MouseEvent| on_confirm.emit((*selected).clone()))
+    };
+    let count = selected.len();
     html! {
-        <div class={classes!("cards-modal")} onclick={hideModal} >
+        <div class={classes!("cards-modal")} onclick={hide_modal} >
            <div class={classes!("modal-content")} onclick={Callback::from(|e: MouseEvent|{
             e.stop_propagation();
         })} >
-               <div class={classes!("trade-row-container")}>
-                 <TradeRow cards={props.cards.clone()} />
+                <p class={classes!("modal-prompt")}>{ &props.prompt }</p>
+                { for zones.into_iter().map(|zone| html! {
+                    <div>
+                        <p class={classes!("modal-zone")}>{ zone }</p>
+                        <div class={classes!("trade-row")}>
+                            { for props.options.iter().filter(|(t, _)| zone_name(t) == zone).map(|(t, card)| html! {
+                                <div class={classes!("trade-row-elem", selected.contains(t).then_some("selected"))}>
+                                    <CardComponent card={*card} onclick={toggle(*t)} />
+                                </div>
+                            }) }
+                        </div>
+                    </div>
+                }) }
+                <div class={classes!("modal-buttons")}>
+                    <span>{ format!("{count} selected, {} to {}", props.min, props.max) }</span>
+                    <button disabled={count < props.min || count > props.max} onclick={onconfirm}>{"Confirm"}</button>
+                    if props.min == 0 {
+                        <button onclick={props.on_cancel.reform(|_: MouseEvent| ())}>{"Cancel"}</button>
+                    }
                 </div>
             </div>
         </div>
     }
 }
 
+/// Every card of a public pile, one per name with its count
 #[function_component]
-fn CardModal(props: &CardModalProps) -> Html {
+fn PileModal(props: &PileModalProps) -> Html {
+    let (title, cards) = &props.pile;
+    let mut groups: Vec<(&Card, usize)> = vec![];
+    for card in cards {
+        match groups.iter_mut().find(|(c, _)| c.get_name() == card.get_name()) {
+            Some((_, n)) => *n += 1,
+            None => groups.push((card, 1)),
+        }
+    }
+    html! {
+        <div class={classes!("cards-modal")} onclick={props.on_close.reform(|_: MouseEvent| ())}>
+           <div class={classes!("modal-content", "pile-modal")} onclick={Callback::from(|e: MouseEvent|{
+            e.stop_propagation();
+        })} >
+                <p class={classes!("modal-prompt")}>{ format!("{title} ({} cards)", cards.len()) }</p>
+                <div class={classes!("trade-row")}>
+                    { for groups.into_iter().map(|(card, n)| html! {
+                        <div class={classes!("trade-row-elem")}>
+                            <CardComponent card={*card} />
+                            <div class={classes!("pile-info")}>{ format!("x{n}") }</div>
+                        </div>
+                    }) }
+                </div>
+                <div class={classes!("modal-buttons")}>
+                    <button onclick={props.on_close.reform(|_: MouseEvent| ())}>{"Close"}</button>
+                </div>
+            </div>
+        </div>
+    }
+}
+
+/// Picks one side of an `Ability::Or`, or declines both
+#[function_component]
+fn ChoiceModal(props: &ChoiceModalProps) -> Html {
     html! {
         <div class={classes!("cards-modal")}>
            <div class={classes!("modal-content")}>
-                 <CardComponent card={props.card.clone()} />
+                <p class={classes!("modal-prompt")}>{ &props.prompt }</p>
+                <div class={classes!("modal-buttons")}>
+                    { for props.branches.iter().enumerate().map(|(i, branch)| html! {
+                        <button onclick={props.on_choose.reform(move |_: MouseEvent| i)}>{ branch }</button>
+                    }) }
+                    if let Some(on_cancel) = &props.on_cancel {
+                        <button onclick={on_cancel.reform(|_: MouseEvent| ())}>{"Cancel"}</button>
+                    }
+                </div>
             </div>
         </div>
     }
@@ -284,29 +913,386 @@ fn CardModal(props: &CardModalProps) -> Html {
 
 #[function_component]
 fn Board(props: &BoardProps) -> Html {
+    let inspect = |title: &str, cards: &Vec<Card>| {
+        let (title, cards) = (title.to_string(), cards.clone());
+        Some(props.on_inspect.reform(move |_: MouseEvent| (title.clone(), cards.clone())))
+    };
     html! {
         <div class={classes!("game-board")}>
-            <CardDeck deck={props.explorers.clone()} visible={true}/>
-            <TradeRow cards={props.trade_row.clone()}/>
+            <CardDeck deck={props.explorers.clone()} visible={true} onclick={props.on_action.clone().map(|cb| cb.reform(|_: MouseEvent| Action::BuyExplorer))} on_inspect={inspect("Explorers", &props.explorers)} />
+            <TradeRow cards={props.trade_row.clone()} on_click={props.on_action.clone().map(|cb| cb.reform(Action::Buy))} />
             <CardDeck deck={props.trade_deck.clone()} visible={false}/>
-            <CardDeck deck={props.scrap.clone()} visible={true}/>
+            <CardDeck deck={props.scrap.clone()} visible={true} on_inspect={inspect("Scrap heap", &props.scrap)} />
+        </div>
+    }
+}
+
+/// Dialog answering the pending decision, `legal` holds the selectable targets
+fn decision_modal(gs: &Rc<GameState>, pending: &PendingView, legal: &[Action], on_action: &Callback<Action>) -> Html {
+    // a new state means a new decision, the dialog starts over
+    let key = Rc::as_ptr(gs) as usize;
+    match pending.clone() {
+        PendingView::Cards { prompt, min, max, single } => {
+            let options = legal.iter()
+                .filter_map(|action| match action {
+                    Action::Select(t) => GameLogic::target_card(gs, t).map(|c| (*t, c)),
+                    _ => None,
+                })
+                .collect::<Vec<_>>();
+            // a mandatory decision without card to select is skipped like an optional one
+            let min = min.min(options.len());
+            let on_confirm = {
+                let on_action = on_action.clone();
+                Callback::from(move |targets: Vec<Target>| {
+                    if single && targets.is_empty() {
+                        on_action.emit(Action::Decline);
+                    }
+                    for target in targets {
+                        on_action.emit(Action::Select(target));
+                    }
+                    if !single {
+                        on_action.emit(Action::Confirm);
+                    }
+                })
+            };
+            html! {
+                <CardsModal key={key} {prompt} {options} {min} {max}
+                    {on_confirm} on_cancel={on_action.reform(|_| Action::Decline)} />
+            }
+        }
+        PendingView::Choice { branches } => html! {
+            <ChoiceModal key={key} prompt={"Choose one"} {branches}
+                on_choose={on_action.reform(Action::Choose)} on_cancel={Some(on_action.reform(|_| Action::Decline))} />
+        },
+    }
+}
+
+#[function_component]
+fn GameView(props: &GameViewProps) -> Html {
+    let gs = props.state.clone();
+    let current = gs.turn % 2;
+    // nothing is clickable once the game is over
+    let on_action = props.on_action.clone().filter(|_| !gs.is_over());
+    let bottom = props.viewer.unwrap_or(1);
+    let inspected = use_state(|| None::<Pile>);
+    let on_inspect = {
+        let inspected = inspected.clone();
+        Callback::from(move |pile: Pile| inspected.set(Some(pile)))
+    };
+    let on_close = {
+        let inspected = inspected.clone();
+        Callback::from(move |_| inspected.set(None))
+    };
+    let remote = props.remote.as_ref().map(|v| (v, &v.players));
+    let player = |id: i32| {
+        let Ok(p) = gs.get_player(id) else {
+            return html! {};
+        };
+        let (hand_visible, abilities) = match remote {
+            Some((_, players)) => (players[id as usize].hand.is_some(), players[id as usize].abilities.clone()),
+            None => (props.viewer.is_none_or(|v| v == id), p.hand.abilities.iter().map(|a| a.to_string()).collect()),
+        };
+        html! {
+            <Player id={id} p1={id == bottom} active={current == id} on_action={on_action.clone()}
+                hand={p.hand.playable.clone()} {hand_visible} {abilities}
+                discard={p.discard.clone()} deck={p.deck.clone()} played={p.hand.played.clone()}
+                authority={p.authority} trade={p.hand.trade} damages={p.hand.damage} on_inspect={on_inspect.clone()}
+                clock_ms={gs.clock.map(|c| c.remaining_ms[id as usize])} />
+        }
+    };
+
+    html! {
+        <div>
+            { player(1 - bottom) }
+            <Board explorers={gs.explorers.clone()} trade_row={gs.trade_row.clone()} trade_deck={gs.trade_deck.clone()} scrap={gs.scrap.clone()} on_action={on_action.clone()} on_inspect={on_inspect.clone()} />
+            if let Some(on_action) = &on_action {
+                {
+                    match remote {
+                        Some((view, _)) => view.pending.as_ref()
+                            .map_or(html! {}, |p| decision_modal(&gs, p, &view.legal_actions, on_action)),
+                        None => PendingView::of(&gs)
+                            .map_or(html! {}, |p| decision_modal(&gs, &p, &GameLogic::legal_actions(&gs), on_action)),
+                    }
+                }
+            }
+            { player(bottom) }
+            if let Some(pile) = &*inspected {
+                <PileModal pile={pile.clone()} {on_close} />
+            }
+        </div>
+    }
+}
+
+#[function_component]
+fn ReplayViewer(props: &ReplayViewerProps) -> Html {
+    let text = use_state(String::new);
+    let loaded = use_state(|| None::<Rc<(Replay, Vec<GameState>)>>);
+    let index = use_state(|| 0usize);
+    let error = use_state(|| None::<String>);
+
+    let oninput = {
+        let text = text.clone();
+        Callback::from(move |e: InputEvent| {
+            let area: HtmlTextAreaElement = e.target_unchecked_into();
+            text.set(area.value());
+        })
+    };
+    let onload = {
+        let (text, loaded, index, error) = (text.clone(), loaded.clone(), index.clone(), error.clone());
+        Callback::from(move |_: MouseEvent| {
+            match text.parse::<Replay>().and_then(|r| replay(&r).map(|states| (r, states))) {
+                Ok(l) => {
+                    loaded.set(Some(Rc::new(l)));
+                    index.set(0);
+                    error.set(None);
+                }
+                Err(e) => error.set(Some(e)),
+            }
+        })
+    };
+    let onclose = {
+        let on_close = props.on_close.clone();
+        Callback::from(move |_: MouseEvent| on_close.emit(()))
+    };
+
+    let content = match &*loaded {
+        None => html! {
+            <div class={classes!("replay-load")}>
+                <textarea rows="12" cols="80" placeholder="Paste a replay here" value={(*text).clone()} {oninput} />
+                <button onclick={onload}>{"Load"}</button>
+                if let Some(e) = &*error {
+                    <p class={classes!("replay-error")}>{e}</p>
+                }
+            </div>
+        },
+        Some(l) => {
+            let (record, states) = (&l.0, &l.1);
+            let last = states.len() - 1;
+            let step = |delta: i64| {
+                let index = index.clone();
+                Callback::from(move |_: MouseEvent| index.set((*index as i64 + delta).clamp(0, last as i64) as usize))
+            };
+            let onturn = {
+                let (index, record) = (index.clone(), record.clone());
+                Callback::from(move |e: Event| {
+                    let input: HtmlInputElement = e.target_unchecked_into();
+                    if let Some(i) = input.value().parse::<i32>().ok().and_then(|turn| record.turn_start(turn - 1)) {
+                        index.set(i);
+                    }
+                })
+            };
+            let onresume = {
+                let on_resume = props.on_resume.clone();
+                let record = record.truncated(*index);
+                Callback::from(move |_: MouseEvent| on_resume.emit(record.clone()))
+            };
+            let gs = Rc::new(states[*index].clone());
+            html! {
+                <div>
+                    <div class={classes!("replay-controls")}>
+                        <button onclick={step(-1)}>{"Step back"}</button>
+                        <span>{format!("step {}/{} - turn {}", *index, last, gs.turn + 1)}</span>
+                        <button onclick={step(1)}>{"Step forward"}</button>
+                        <label>{"Jump to turn "}<input type="number" min="1" onchange={onturn} /></label>
+                        <button onclick={onresume}>{"Continue from here"}</button>
+                    </div>
+                    if *index > 0 {
+                        <p class={classes!("replay-action")}>{ format!("{}: {}", record.players[(states[*index - 1].turn % 2) as usize], record.steps[*index - 1].action) }</p>
+                    }
+                    <GameView state={gs} />
+                </div>
+            }
+        }
+    };
+    html! {
+        <div class={classes!("replay-viewer")}>
+            <button onclick={onclose}>{"Close"}</button>
+            { content }
         </div>
     }
 }
 
 #[function_component]
 fn App() -> Html {
-    let gs = GameState::new();
-    let p1 = gs.players.0;
-    let p2 = gs.players.1;
+    let mode = use_state(|| Mode::HotSeat);
+    let game = use_reducer(|| GameModel::new(Mode::HotSeat.new_game()));
+    let replay_open = use_state(|| false);
+    let coach = use_state(|| false);
+    // turn the players last agreed to look at, the device is passed when it changes
+    let seen_turn = use_state(|| 0);
+    let thinking = use_state(|| false);
+    // actions of the computer's turn still to be shown
+    let playback = use_state(Vec::<Action>::new);
+    // id of the request the computer is working on, older answers are dropped
+    let request_id = use_mut_ref(|| None::<u64>);
+    let gs = game.state.clone();
+
+    let bridge = {
+        let (thinking, playback, game, request_id) = (thinking.setter(), playback.setter(), game.dispatcher(), request_id.clone());
+        use_memo((), move |_| {
+            AiWorker::spawner()
+                .callback(move |response: AiResponse| {
+                    if *request_id.borrow() != Some(response.id) {
+                        return;
+                    }
+                    request_id.borrow_mut().take();
+                    match response.actions.and_then(|actions| actions.iter().map(|a| a.parse()).collect()) {
+                        Ok(actions) => playback.set(actions),
+                        Err(e) => game.dispatch(GameMsg::Fail(e)),
+                    }
+                    thinking.set(false);
+                })
+                .spawn("/ai_worker.js")
+        })
+    };
+    {
+        let (gs, record, thinking, request_id) = (gs.clone(), game.record.clone(), thinking.clone(), request_id.clone());
+        use_effect_with((game.version, *mode, playback.is_empty(), *thinking), move |(_, mode, idle, busy)| {
+            if let Mode::Computer(difficulty) = *mode {
+                if *idle && !*busy && gs.turn % 2 == 1 && !gs.is_over() {
+                    let id = rand::random();
+                    *request_id.borrow_mut() = Some(id);
+                    thinking.set(true);
+                    bridge.send(AiRequest { id, replay: record.to_string(), playouts: difficulty.playouts() });
+                }
+            }
+        });
+    }
+    {
+        let (game, playback) = (game.dispatcher(), playback.clone());
+        use_effect_with((*playback).clone(), move |actions| {
+            let timeout = actions.split_first().map(|(first, rest)| {
+                let (first, rest) = (*first, rest.to_vec());
+                Timeout::new(PLAYBACK_DELAY_MS, move || {
+                    game.dispatch(GameMsg::Apply(first));
+                    playback.set(rest);
+                })
+            });
+            move || drop(timeout)
+        });
+    }
+
+    let restart = {
+        let (game, playback, thinking, request_id) = (game.dispatcher(), playback.setter(), thinking.setter(), request_id.clone());
+        move |record: Replay| {
+            // the computer may still answer for the previous game, it will be ignored
+            request_id.borrow_mut().take();
+            thinking.set(false);
+            playback.set(vec![]);
+            game.dispatch(GameMsg::Load(record));
+        }
+    };
+    let open_replay = {
+        let replay_open = replay_open.clone();
+        Callback::from(move |_: MouseEvent| replay_open.set(true))
+    };
+    let on_close = {
+        let replay_open = replay_open.clone();
+        Callback::from(move |_| replay_open.set(false))
+    };
+    let on_resume = {
+        let (restart, replay_open) = (restart.clone(), replay_open.clone());
+        Callback::from(move |record: Replay| {
+            restart(record);
+            replay_open.set(false);
+        })
+    };
+    let computer_turn = matches!(*mode, Mode::Computer(_)) && gs.turn % 2 == 1;
+    let on_action = {
+        let game = game.clone();
+        (!computer_turn).then(|| Callback::from(move |action: Action| game.dispatch(GameMsg::Request(action))))
+    };
+    // casual games only, while the computer is not playing
+    let undo_shown = !computer_turn && !*thinking && playback.is_empty() && !gs.is_over();
+    let history_button = |label: &str, enabled: bool, msg: fn() -> GameMsg| {
+        let game = game.dispatcher();
+        html! { <button disabled={!enabled} onclick={Callback::from(move |_: MouseEvent| game.dispatch(msg()))}>{ label.to_string() }</button> }
+    };
+    let on_confirm = {
+        let (game, action) = (game.dispatcher(), game.unconfirmed);
+        Callback::from(move |i: usize| match (i, action) {
+            (0, Some(action)) => game.dispatch(GameMsg::Apply(action)),
+            _ => game.dispatch(GameMsg::Cancel),
+        })
+    };
+    let on_ready = {
+        let (seen_turn, turn) = (seen_turn.clone(), gs.turn);
+        Callback::from(move |_| seen_turn.set(turn))
+    };
+    let new_game = {
+        let (restart, mode) = (restart.clone(), *mode);
+        Callback::from(move |_: MouseEvent| restart(mode.new_game()))
+    };
+    let on_mode = {
+        let (restart, mode) = (restart.clone(), mode.clone());
+        Callback::from(move |e: Event| {
+            let select: HtmlSelectElement = e.target_unchecked_into();
+            if let Some((m, _)) = select.value().parse::<usize>().ok().and_then(|i| Mode::ALL.get(i)) {
+                mode.set(*m);
+                restart(m.new_game());
+            }
+        })
+    };
+    let viewer = match *mode {
+        _ if gs.is_over() => None,
+        Mode::HotSeat | Mode::Online => Some(gs.turn % 2),
+        Mode::Computer(_) => Some(0),
+    };
 
     html! {
         <div>
-            <Player p1={false} discard={p1.discard.clone()} deck={p1.deck.clone()} played={p1.hand.played.clone()} authority={p1.authority.clone()} trade={p1.hand.trade} damages={p1.hand.damage} />
-            <Board explorers={gs.explorers.clone()} trade_row={gs.trade_row.clone()} trade_deck={gs.trade_deck} scrap={gs.scrap.clone()} />
-            <Player p1={true} discard={p2.discard.clone()} deck={p2.deck.clone()} played={p2.hand.played.clone()} authority={p2.authority.clone()} trade={p2.hand.trade} damages={p2.hand.damage} />
-            <CardsModal cards={gs.explorers} />
-            //<CardModal card={CardFactory::scout()} />
+            if *replay_open {
+                <ReplayViewer {on_resume} {on_close} />
+            } else {
+                <div class={classes!("game-controls")}>
+                    <button class={classes!("replay-open")} onclick={open_replay}>{"Replays"}</button>
+                    <select onchange={on_mode}>
+                        { for Mode::ALL.iter().enumerate().map(|(i, (m, label))| html! {
+                            <option value={i.to_string()} selected={*m == *mode}>{ *label }</option>
+                        }) }
+                    </select>
+                    if *mode != Mode::Online {
+                        <button onclick={new_game}>{"New game"}</button>
+                        <label class={classes!("coach-toggle")}>
+                            <input type="checkbox" checked={*coach} onchange={{
+                                let coach = coach.clone();
+                                Callback::from(move |_: Event| coach.set(!*coach))
+                            }} />
+                            {"Coach"}
+                        </label>
+                        if undo_shown {
+                            { history_button("Undo", game.history.can_undo(), || GameMsg::Undo) }
+                            { history_button("Redo", game.history.can_redo(), || GameMsg::Redo) }
+                        }
+                        if *thinking {
+                            <span class={classes!("thinking")}>{"The computer is thinking..."}</span>
+                        } else if !playback.is_empty() {
+                            <span class={classes!("thinking")}>{ format!("The computer plays: {}", playback[0]) }</span>
+                        }
+                        if let Some(winner) = gs.winner() {
+                            <span class={classes!("game-over")}>{ format!("Player {} wins!", winner + 1) }</span>
+                        }
+                        if let Some(e) = &game.error {
+                            <span class={classes!("game-error")}>{ e }</span>
+                        }
+                    }
+                </div>
+                if *mode == Mode::Online {
+                    <online::OnlineGame />
+                } else if *mode == Mode::HotSeat && *seen_turn != gs.turn && !gs.is_over() {
+                    <PassDevice player={gs.turn % 2} {on_ready} />
+                } else {
+                    <GameView state={gs.clone()} {viewer} {on_action} />
+                    if let Some(player) = viewer.filter(|_| *coach) {
+                        <Coach state={gs.clone()} {player} />
+                    }
+                    <GameLog events={game.log.clone()} />
+                    if game.unconfirmed.is_some() {
+                        <ChoiceModal prompt={"This reveals new cards: the previous actions of the turn can no longer be undone."}
+                            branches={["Continue".to_string(), "Cancel".to_string()]} on_choose={on_confirm} />
+                    }
+                }
+            }
         </div>
     }
 }
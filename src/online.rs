@@ -0,0 +1,290 @@
+use std::rc::Rc;
+use futures::channel::mpsc::{unbounded, UnboundedSender};
+use futures::{SinkExt, StreamExt};
+use gloo_net::websocket::futures::WebSocket;
+use gloo_net::websocket::Message;
+use gloo_storage::{LocalStorage, Storage};
+use serde::{Deserialize, Serialize};
+use wasm_bindgen_futures::spawn_local;
+use gloo_timers::callback::Interval;
+use web_sys::{HtmlInputElement, HtmlSelectElement};
+use yew::prelude::*;
+use star_realms_engine::clock::{TimeControl, TimeoutAction};
+use star_realms_engine::gamelogic::{Action, GameLogic};
+use star_realms_engine::protocol::{ClientMessage, ServerMessage};
+use star_realms_engine::star_realms::GameState;
+use star_realms_engine::view::TableView;
+use crate::GameView;
+
+const DEFAULT_SERVER: &str = "ws://localhost:9000";
+const SESSION_KEY: &str = "star-realms-session";
+/// Time controls offered for new lobbies: total and increment, in seconds
+const TIME_CONTROLS: [(&str, Option<(u64, u64)>); 4] = [
+    ("No clock", None),
+    ("3 min + 2 s", Some((180, 2))),
+    ("5 min + 5 s", Some((300, 5))),
+    ("10 min + 10 s", Some((600, 10))),
+];
+/// How often a running clock is redrawn
+const CLOCK_REFRESH_MS: u32 = 200;
+
+/// A seat kept in the local storage, to take it back after a reload or a lost connection
+#[derive(Clone, PartialEq, Serialize, Deserialize)]
+struct Session {
+    server: String,
+    code: String,
+    token: String,
+}
+
+/// What the server told us
+#[derive(Default)]
+struct OnlineModel {
+    connected: bool,
+    code: Option<String>,
+    /// Missing for spectators
+    seat: Option<i32>,
+    view: Option<Rc<TableView>>,
+    state: Option<Rc<GameState>>,
+    /// When the last state arrived, in milliseconds, to count the running clock down
+    received_at: f64,
+    /// Seats whose player lost the connection
+    absent: Vec<i32>,
+    error: Option<String>,
+}
+
+enum OnlineMsg {
+    Connected(bool),
+    Received(Box<ServerMessage>),
+    Fail(String),
+}
+
+impl Reducible for OnlineModel {
+    type Action = OnlineMsg;
+
+    fn reduce(self: Rc<Self>, msg: Self::Action) -> Rc<Self> {
+        let mut model = OnlineModel {
+            connected: self.connected,
+            code: self.code.clone(),
+            seat: self.seat,
+            view: self.view.clone(),
+            state: self.state.clone(),
+            received_at: self.received_at,
+            absent: self.absent.clone(),
+            error: None,
+        };
+        let message = match msg {
+            OnlineMsg::Connected(connected) => {
+                model.connected = connected;
+                return Rc::new(model);
+            }
+            OnlineMsg::Fail(e) => {
+                model.error = Some(e);
+                return Rc::new(model);
+            }
+            OnlineMsg::Received(message) => *message,
+        };
+        match message {
+            ServerMessage::Joined { code, seat, .. } => {
+                model.code = Some(code);
+                model.seat = seat;
+                model.absent = vec![];
+            }
+            ServerMessage::State { view } => match view.to_state() {
+                Ok(state) => {
+                    model.state = Some(Rc::new(state));
+                    model.view = Some(Rc::new(*view));
+                    model.received_at = js_sys::Date::now();
+                }
+                Err(e) => model.error = Some(e),
+            },
+            ServerMessage::Presence { seat, connected } => {
+                model.absent.retain(|s| *s != seat);
+                if !connected {
+                    model.absent.push(seat);
+                }
+            }
+            ServerMessage::Error { message } => model.error = Some(message),
+        }
+        Rc::new(model)
+    }
+}
+
+/// Opens a connection to `server`, whose messages go to `model`
+fn connect(server: &str, model: UseReducerDispatcher<OnlineModel>) -> Result<UnboundedSender<ClientMessage>, String> {
+    let socket = WebSocket::open(server).map_err(|e| e.to_string())?;
+    let (mut sink, mut incoming) = socket.split();
+    let (sender, mut outgoing) = unbounded::<ClientMessage>();
+    spawn_local(async move {
+        while let Some(message) = outgoing.next().await {
+            let text = serde_json::to_string(&message).expect("Client messages are serializable");
+            if sink.send(Message::Text(text)).await.is_err() {
+                break;
+            }
+        }
+    });
+    model.dispatch(OnlineMsg::Connected(true));
+    let server = server.to_string();
+    spawn_local(async move {
+        while let Some(Ok(message)) = incoming.next().await {
+            if let Message::Text(text) = message {
+                match serde_json::from_str(&text) {
+                    Ok(message) => {
+                        // the token is only sent once, keep it to take the seat back later
+                        if let ServerMessage::Joined { code, token: Some(token), .. } = &message {
+                            let session = Session { server: server.clone(), code: code.clone(), token: token.clone() };
+                            let _ = LocalStorage::set(SESSION_KEY, session);
+                        }
+                        model.dispatch(OnlineMsg::Received(Box::new(message)))
+                    }
+                    Err(e) => model.dispatch(OnlineMsg::Fail(e.to_string())),
+                }
+            }
+        }
+        model.dispatch(OnlineMsg::Connected(false));
+    });
+    Ok(sender)
+}
+
+/// A game owned by the server of `server/`: create or join a lobby, then play or watch it
+#[function_component]
+pub fn OnlineGame() -> Html {
+    let model = use_reducer(OnlineModel::default);
+    let server = use_state(|| LocalStorage::get::<Session>(SESSION_KEY).map_or(DEFAULT_SERVER.to_string(), |s| s.server));
+    let code = use_state(String::new);
+    let time_control = use_state(|| 0usize);
+    let on_timeout = use_state(|| TimeoutAction::EndTurn);
+    let sender = use_mut_ref(|| None::<UnboundedSender<ClientMessage>>);
+    let session = LocalStorage::get::<Session>(SESSION_KEY).ok();
+
+    let send = {
+        let (model, sender, server) = (model.clone(), sender.clone(), server.clone());
+        Rc::new(move |message: ClientMessage| {
+            let alive = sender.borrow().as_ref().is_some_and(|s| !s.is_closed()) && model.connected;
+            if !alive {
+                match connect(&server, model.dispatcher()) {
+                    Ok(s) => *sender.borrow_mut() = Some(s),
+                    Err(e) => return model.dispatch(OnlineMsg::Fail(e)),
+                }
+            }
+            if let Some(s) = sender.borrow().as_ref() {
+                let _ = s.unbounded_send(message);
+            }
+        })
+    };
+    let on_server = {
+        let server = server.clone();
+        Callback::from(move |e: Event| {
+            let input: HtmlInputElement = e.target_unchecked_into();
+            server.set(input.value());
+        })
+    };
+    let on_code = {
+        let code = code.clone();
+        Callback::from(move |e: Event| {
+            let input: HtmlInputElement = e.target_unchecked_into();
+            code.set(input.value());
+        })
+    };
+    let on_time_control = {
+        let time_control = time_control.clone();
+        Callback::from(move |e: Event| {
+            let select: HtmlSelectElement = e.target_unchecked_into();
+            time_control.set(select.value().parse().unwrap_or(0));
+        })
+    };
+    let on_timeout_change = {
+        let on_timeout = on_timeout.clone();
+        Callback::from(move |e: Event| {
+            let select: HtmlSelectElement = e.target_unchecked_into();
+            on_timeout.set(if select.value() == "forfeit" { TimeoutAction::Forfeit } else { TimeoutAction::EndTurn });
+        })
+    };
+    let clock = TIME_CONTROLS[*time_control].1.map(|(total, increment)| TimeControl {
+        total_ms: total * 1000,
+        increment_ms: increment * 1000,
+        on_timeout: *on_timeout,
+    });
+
+    // the server only sends the clocks with each state, the running one is counted down here
+    let running = model.connected && model.view.as_ref().is_some_and(|v| v.clock.is_some() && v.winner.is_none());
+    let redraw = use_force_update();
+    use_effect_with(running, move |running| {
+        let interval = running.then(|| Interval::new(CLOCK_REFRESH_MS, move || redraw.force_update()));
+        move || drop(interval)
+    });
+
+    let button = |label: &str, message: ClientMessage| {
+        let send = send.clone();
+        html! { <button onclick={Callback::from(move |_: MouseEvent| send(message.clone()))}>{ label.to_string() }</button> }
+    };
+
+    let controls = html! {
+        <div class={classes!("game-controls")}>
+            <label>{"Server "}<input value={(*server).clone()} onchange={on_server} /></label>
+            <select onchange={on_time_control}>
+                { for TIME_CONTROLS.iter().enumerate().map(|(i, (label, _))| html! {
+                    <option value={i.to_string()} selected={i == *time_control}>{ *label }</option>
+                }) }
+            </select>
+            if clock.is_some() {
+                <select onchange={on_timeout_change}>
+                    <option value="end" selected={*on_timeout == TimeoutAction::EndTurn}>{"Timeout ends the turn"}</option>
+                    <option value="forfeit" selected={*on_timeout == TimeoutAction::Forfeit}>{"Timeout loses the game"}</option>
+                </select>
+            }
+            { button("Create lobby", ClientMessage::Create { clock }) }
+            <label>{"Code "}<input value={(*code).clone()} onchange={on_code} size="6" /></label>
+            { button("Join", ClientMessage::Join { code: (*code).clone() }) }
+            { button("Spectate", ClientMessage::Spectate { code: (*code).clone() }) }
+            if let Some(s) = &session {
+                { button(&format!("Reconnect to {}", s.code), ClientMessage::Reconnect { code: s.code.clone(), token: s.token.clone() }) }
+            }
+        </div>
+    };
+    let status = html! {
+        <div class={classes!("game-controls")}>
+            if let Some(code) = &model.code {
+                <span>{ match model.seat {
+                    Some(seat) => format!("Lobby {code}, you are player {}", seat + 1),
+                    None => format!("Lobby {code}, spectating"),
+                } }</span>
+            }
+            if !model.connected && model.code.is_some() {
+                <span class={classes!("game-error")}>{"Connection lost"}</span>
+            }
+            { for model.absent.iter().map(|seat| html! {
+                <span class={classes!("game-error")}>{ format!("Player {} left", seat + 1) }</span>
+            }) }
+            if let Some(winner) = model.view.as_ref().and_then(|v| v.winner) {
+                <span class={classes!("game-over")}>{ format!("Player {} wins!", winner + 1) }</span>
+            }
+            if let Some(e) = &model.error {
+                <span class={classes!("game-error")}>{ e }</span>
+            }
+        </div>
+    };
+    let game = match (&model.state, &model.view) {
+        (Some(state), Some(view)) => {
+            let my_turn = model.connected && model.seat == Some(state.turn % 2);
+            let state = if running {
+                let elapsed = (js_sys::Date::now() - model.received_at).max(0.0) as u64;
+                Rc::new(GameLogic::spend_time(state, elapsed))
+            } else {
+                state.clone()
+            };
+            let on_action = my_turn.then(|| {
+                let send = send.clone();
+                Callback::from(move |action: Action| send(ClientMessage::Action { action }))
+            });
+            html! { <GameView {state} viewer={model.seat} remote={view.clone()} {on_action} /> }
+        }
+        _ => html! {},
+    };
+    html! {
+        <div>
+            { controls }
+            { status }
+            { game }
+        </div>
+    }
+}